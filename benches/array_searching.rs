@@ -1,3 +1,5 @@
+// `black_box` re-exports `std::hint::black_box` only on compilers that have it.
+#![allow(clippy::incompatible_msrv)]
 use calliper::utils::black_box;
 use calliper::{Runner, Scenario};
 
@@ -25,15 +27,11 @@ fn bench_linear_search() {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let runner = Runner::default();
+    let mut runner = Runner::default();
     let benches = [
-        Scenario::new(bench_linear_search),
+        Scenario::new(bench_linear_search).reference(),
         Scenario::new(bench_binary_search),
     ];
-    if let Some(results) = runner.run(&benches)? {
-        for res in results.into_iter() {
-            println!("{}", res.parse());
-        }
-    }
+    runner.run(&benches)?;
     Ok(())
 }
@@ -1,5 +1,7 @@
+// `black_box` re-exports `std::hint::black_box` only on compilers that have it.
+#![allow(clippy::incompatible_msrv)]
 use calliper::utils::black_box;
-use calliper::{Runner, Scenario, ScenarioConfig};
+use calliper::{Metric, RegressionPolicy, Runner, Scenario, ScenarioConfig, Threshold};
 
 #[inline(never)]
 #[no_mangle]
@@ -35,12 +37,14 @@ fn run_slow_bench() {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let runner = Runner::default().config(ScenarioConfig::default().branch_sim(true));
+    // When compared against a baseline, fail if instruction count grows by more than 1%.
+    let mut runner = Runner::default()
+        .config(ScenarioConfig::default().branch_sim(true))
+        .policy(RegressionPolicy::new(
+            Metric::InstructionReads,
+            Threshold::Relative(1.0),
+        ));
     let benches = [Scenario::new(run_bench), Scenario::new(run_slow_bench)];
-    if let Some(results) = runner.run(&benches)? {
-        for res in results.into_iter() {
-            println!("{}", res.parse());
-        }
-    }
+    runner.run(&benches)?;
     Ok(())
 }
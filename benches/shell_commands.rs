@@ -1,10 +1,15 @@
 //! This benchmark shows how to use Calliper to benchmark non-Rust code, e.g. other executables.
 use std::process::Command;
+use std::time::Duration;
 
 use calliper::{Runner, Scenario, ScenarioConfig};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let runner = Runner::default().config(ScenarioConfig::default().branch_sim(true));
+    let mut runner = Runner::default().config(
+        ScenarioConfig::default()
+            .branch_sim(true)
+            .timeout(Duration::from_secs(60)),
+    );
     let mut echo_short_message = Command::new("echo");
     echo_short_message.arg("Hello, world!");
     let mut echo_long_message = Command::new("echo");
@@ -15,10 +20,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Scenario::new_with_command(echo_long_message).name("Long"),
         Scenario::new_with_command(always_fails).name("Always fails"),
     ];
-    if let Some(results) = runner.run(&benches)? {
-        for res in results.into_iter() {
-            println!("{}", res.parse());
-        }
-    }
+    runner.run(&benches)?;
     Ok(())
 }
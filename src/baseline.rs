@@ -0,0 +1,94 @@
+//! Named baselines, i.e. scenario results saved for later comparison.
+//!
+//! Each baseline is stored as a JSON file at
+//! `target/calliper/<bench>/<scenario>/<baseline name>.json`. Names that aren't safe to use as file
+//! names have unsafe characters replaced and a short hash appended.
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::config::ScenarioConfig;
+use crate::parser::ParsedCallgrindOutput;
+use crate::utils;
+
+/// Results of a single scenario, saved under a name with `--save-baseline <name>`.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Baseline {
+    /// Name of the baseline.
+    pub name: String,
+    /// Name of the scenario.
+    pub scenario: String,
+    /// Name of the bench binary the scenario belongs to.
+    pub bench: String,
+    /// Configuration the scenario was run with (with runner defaults applied).
+    pub config: ScenarioConfig,
+    /// Parsed Callgrind results of the scenario.
+    pub results: ParsedCallgrindOutput,
+    /// Information about the run.
+    pub metadata: BaselineMetadata,
+}
+
+/// Auxiliary information on a [`Baseline`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BaselineMetadata {
+    /// Time of the run, in seconds since Unix epoch.
+    pub timestamp: u64,
+    /// Version of Calliper that produced the baseline.
+    pub calliper_version: String,
+}
+
+impl Baseline {
+    pub(crate) fn new(
+        name: &str,
+        scenario: &str,
+        config: ScenarioConfig,
+        results: ParsedCallgrindOutput,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            scenario: scenario.to_owned(),
+            bench: utils::bench_name(),
+            config,
+            results,
+            metadata: BaselineMetadata {
+                timestamp: utils::unix_timestamp(),
+                calliper_version: env!("CARGO_PKG_VERSION").to_owned(),
+            },
+        }
+    }
+    /// Returns a path of a baseline file for a given scenario of current bench binary.
+    pub(crate) fn path(scenario: &str, name: &str) -> PathBuf {
+        utils::calliper_dir()
+            .join(utils::sanitize_file_name(&utils::bench_name()))
+            .join(utils::sanitize_file_name(scenario))
+            .join(format!("{}.json", utils::sanitize_file_name(name)))
+    }
+    /// Loads a baseline for a given scenario of current bench binary. Returns `None` if there's
+    /// no such baseline.
+    pub(crate) fn load(scenario: &str, name: &str) -> io::Result<Option<Self>> {
+        let path = Self::path(scenario, name);
+        let context = |e: io::Error| {
+            io::Error::new(
+                e.kind(),
+                format!("could not load baseline `{}`: {}", path.display(), e),
+            )
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(context(e)),
+        };
+        serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|e| context(e.into()))
+    }
+    /// Stores baseline in Calliper directory, overwriting previous baseline with the same name.
+    pub(crate) fn save(&self) -> io::Result<()> {
+        let path = Self::path(&self.scenario, &self.name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(path, contents)
+    }
+}
@@ -1,9 +1,14 @@
 use std::ffi::OsStr;
 /// Functions for spawning Callgrind subprocesses and mapping Calliper configuration to Callgrind
 /// command line options.
-use std::process::{Command, Stdio};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
 
 use crate::config::ScenarioConfig;
+use crate::error::CalliperError;
+use crate::interrupt::InterruptHandler;
+use crate::parser::find_output_parts;
 use crate::scenario::Scenario;
 use crate::utils;
 
@@ -26,14 +31,19 @@ fn prepare_command(
     } else {
         valgrind_without_aslr(valgrind, &get_arch())
     };
-    command.stdout(Stdio::piped());
-    command.stderr(Stdio::piped());
+    // Output of benchmarked process is discarded anyways; piping it without reading would stall
+    // processes with a lot of output.
+    command.stdout(Stdio::null());
+    command.stderr(Stdio::null());
+    // Callgrind gets a process group of it's own, so that it can be killed along with it's children.
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
     command.arg("--tool=callgrind");
-    command.arg(&format!(
+    command.arg(format!(
         "--branch-sim={}",
         format_bool(config.get_branch_sim())
     ));
-    command.arg(&format!(
+    command.arg(format!(
         "--collect-bus={}",
         format_bool(config.get_collect_bus())
     ));
@@ -45,7 +55,7 @@ fn prepare_command(
             ("LL", &cache.last_level),
         ] {
             if let Some(params) = &cache_params {
-                command.arg(&format!(
+                command.arg(format!(
                     "--{}={},{},{}",
                     prefix, params.size, params.associativity, params.line_size
                 ));
@@ -56,7 +66,8 @@ fn prepare_command(
         command.arg(format!("--toggle-collect={}", filter));
     }
     if let Some(out_file) = config.get_output_file() {
-        command.arg(format!("--callgrind-out-file=\"{}\"", out_file));
+        // Arguments are passed to Callgrind as they are, without a shell that would strip quotes.
+        command.arg(format!("--callgrind-out-file={}", out_file));
     }
 
     command.arg(measured_command.get_program());
@@ -79,24 +90,84 @@ fn prepare_command(
     command
 }
 
+/// Returns a shell command line that runs a scenario under Callgrind the same way Calliper does.
+/// `run_id` is the ID the Callgrind subprocess uses to look the scenario up.
+pub(crate) fn reproduction_command(
+    scenario: &Scenario,
+    config: &ScenarioConfig,
+    run_id: usize,
+) -> String {
+    shell_command(&prepare_command(
+        &scenario.command,
+        config,
+        run_id.to_string(),
+    ))
+}
+
+/// Renders a command, along with it's environment and working directory, as a line that can be
+/// pasted into a POSIX shell.
+fn shell_command(command: &Command) -> String {
+    let mut words = vec![];
+    if let Some(dir) = command.get_current_dir() {
+        words.push("cd".to_owned());
+        words.push(shell_quote(dir.as_os_str()));
+        words.push("&&".to_owned());
+    }
+    let (set, removed): (Vec<_>, Vec<_>) =
+        command.get_envs().partition(|(_, value)| value.is_some());
+    if !removed.is_empty() {
+        words.push("env".to_owned());
+        for (name, _) in removed {
+            words.push("-u".to_owned());
+            words.push(shell_quote(name));
+        }
+    }
+    for (name, value) in set {
+        let value = value.unwrap_or_default();
+        words.push(format!("{}={}", name.to_string_lossy(), shell_quote(value)));
+    }
+    words.push(shell_quote(command.get_program()));
+    words.extend(command.get_args().map(shell_quote));
+    words.join(" ")
+}
+
+/// Quotes a word for a POSIX shell, unless it consists of characters that are never special.
+fn shell_quote(word: &OsStr) -> String {
+    let word = word.to_string_lossy();
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c);
+    if !word.is_empty() && word.chars().all(is_safe) {
+        word.into_owned()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
 #[derive(
     Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
 )]
 pub(crate) struct CallgrindResultFilename {
     pub(crate) path: String,
     pub(crate) should_delete: bool,
+    /// True if the path was generated by Calliper (`callgrind.out.<pid>`) rather than chosen by the
+    /// user. Only then other files Callgrind produced next to it are removed as well, as files
+    /// sharing a name with user's file are not necessarily Callgrind's.
+    pub(crate) is_generated: bool,
 }
 
 impl Drop for CallgrindResultFilename {
     fn drop(&mut self) {
         if self.should_delete {
-            let _ = std::fs::remove_file(&self.path);
+            let path = Path::new(&self.path);
+            if self.is_generated {
+                for part in find_output_parts(path).unwrap_or_default() {
+                    let _ = std::fs::remove_file(part.path);
+                }
+            }
+            let _ = std::fs::remove_file(path);
         }
     }
 }
 
-pub(crate) type CallgrindError = Box<dyn std::error::Error>;
-
 fn callgrind_output_name(
     pid: u32,
     user_output: &Option<&str>,
@@ -110,31 +181,275 @@ fn callgrind_output_name(
     CallgrindResultFilename {
         path,
         should_delete,
+        is_generated: user_output.is_none(),
+    }
+}
+
+/// Interval between checks whether Callgrind processes have finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// Time given to Callgrind processes to exit after a termination signal was forwarded to them.
+const INTERRUPT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Sends a signal to a child process along with all processes in it's process group.
+#[cfg(unix)]
+fn signal_process_tree(child: &mut Child, signal: i32) -> std::io::Result<()> {
+    // Callgrind is spawned as a leader of a new process group, so it's ID is also an ID of
+    // it's group.
+    let group = -(child.id() as libc::pid_t);
+    // SAFETY: `kill` has no memory safety preconditions.
+    if unsafe { libc::kill(group, signal) } != 0 {
+        let error = std::io::Error::last_os_error();
+        // ESRCH: the whole group is already gone.
+        if error.raw_os_error() != Some(libc::ESRCH) {
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+/// Kills a child process along with all processes in it's process group and reaps it.
+fn kill_process_tree(child: &mut Child) -> std::io::Result<()> {
+    #[cfg(unix)]
+    signal_process_tree(child, libc::SIGKILL)?;
+    #[cfg(not(unix))]
+    child.kill()?;
+    child.wait()?;
+    Ok(())
+}
+
+/// Passes a termination signal on to child process trees and waits for them to exit. Whatever is
+/// left of the process trees after a grace period (e.g. background processes that ignore the
+/// signal) is killed.
+fn interrupt_process_trees(children: &mut [&mut Child], signal: i32) -> std::io::Result<()> {
+    #[cfg(unix)]
+    for child in children.iter_mut() {
+        signal_process_tree(child, signal)?;
+    }
+    #[cfg(not(unix))]
+    let _ = signal;
+    let start = Instant::now();
+    while start.elapsed() < INTERRUPT_GRACE_PERIOD {
+        let mut is_running = false;
+        for child in children.iter_mut() {
+            is_running |= child.try_wait()?.is_none();
+        }
+        if !is_running {
+            break;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    for child in children.iter_mut() {
+        kill_process_tree(child)?;
     }
+    Ok(())
 }
 
+/// Progress of scenarios run by [`spawn_callgrind`]. Scenarios are identified by their position in
+/// the list passed to it.
+pub(crate) enum CallgrindEvent {
+    /// Callgrind process of a scenario was spawned.
+    Started(usize),
+    /// Callgrind process of a scenario has exited (or was killed after a timeout), after running for
+    /// a given time.
+    Finished(
+        usize,
+        Duration,
+        Result<CallgrindResultFilename, CalliperError>,
+    ),
+    /// None of Callgrind processes has exited since the last check.
+    Waiting,
+}
+
+/// A Callgrind process that has not been reaped yet.
+struct RunningScenario<'s> {
+    /// Position of the scenario in the list passed to [`spawn_callgrind`].
+    position: usize,
+    scenario: &'s Scenario,
+    child: Child,
+    output: CallgrindResultFilename,
+    timeout: Option<Duration>,
+    start: Instant,
+}
+
+/// Runs scenarios under Callgrind, with up to `parallelism` Callgrind processes at a time. Each
+/// scenario is paired with it's run ID, that is used by the Callgrind subprocess to look the
+/// scenario up.
+///
+/// `on_event` is called whenever a Callgrind process is spawned or exits, and periodically while
+/// waiting for them. Scenarios are reported as soon as their Callgrind process exits, so they may
+/// be reported out of order. If `on_event` fails, remaining Callgrind processes are killed and the
+/// error is returned.
+///
+/// If a termination signal is received, it's forwarded to running Callgrind processes, their
+/// outputs are removed, remaining scenarios are skipped and the signal is returned, so that the
+/// caller can clean up and terminate.
 pub(crate) fn spawn_callgrind(
-    scenarios: &[&Scenario],
+    scenarios: &[(usize, &Scenario)],
+    default: &ScenarioConfig,
+    parallelism: usize,
+    interrupts: &InterruptHandler,
+    mut on_event: impl FnMut(CallgrindEvent) -> Result<(), CalliperError>,
+) -> Result<Option<i32>, CalliperError> {
+    if parallelism > 1 {
+        check_output_files(scenarios, default)?;
+    }
+    let mut running = vec![];
+    let outcome = poll_callgrind(
+        scenarios,
+        default,
+        parallelism,
+        interrupts,
+        &mut on_event,
+        &mut running,
+    );
+    let mut children: Vec<&mut Child> = running
+        .iter_mut()
+        .map(|running| {
+            // Partial results are of no use to anyone.
+            running.output.should_delete = true;
+            &mut running.child
+        })
+        .collect();
+    match outcome {
+        Ok(Some(signal)) => {
+            let _ = interrupt_process_trees(&mut children, signal);
+        }
+        Ok(None) => {}
+        Err(_) => {
+            for child in children {
+                let _ = kill_process_tree(child);
+            }
+        }
+    }
+    outcome
+}
+
+/// Makes sure that scenarios that may run at the same time do not write to the same output file.
+fn check_output_files(
+    scenarios: &[(usize, &Scenario)],
     default: &ScenarioConfig,
-) -> Result<Vec<CallgrindResultFilename>, CallgrindError> {
-    let mut ret = vec![];
-    for (index, run) in scenarios.iter().enumerate() {
+) -> Result<(), CalliperError> {
+    let mut paths: Vec<String> = vec![];
+    for (_, run) in scenarios {
         let config = default.clone().overwrite(run.config.clone());
-        let mut command = prepare_command(&run.command, &config, index.to_string());
-
-        let child = command.spawn().unwrap();
-        let id = child.id();
-        let name = callgrind_output_name(
-            id,
-            &run.config.get_output_file(),
-            config.get_cleanup_files(),
-        );
-        child.wait_with_output()?;
-        // This is naturally subject to TOCTOU, but it's better than nothing. We'll recheck later on anyways.
-        assert!(std::path::Path::new(&name.path).exists());
-        ret.push(name);
+        if let Some(path) = config.get_output_file() {
+            if paths.iter().any(|other| other == path) {
+                return Err(CalliperError::SharedOutputFile {
+                    path: path.to_owned(),
+                });
+            }
+            paths.push(path.to_owned());
+        }
     }
-    Ok(ret)
+    Ok(())
+}
+
+fn poll_callgrind<'s>(
+    scenarios: &[(usize, &'s Scenario)],
+    default: &ScenarioConfig,
+    parallelism: usize,
+    interrupts: &InterruptHandler,
+    on_event: &mut impl FnMut(CallgrindEvent) -> Result<(), CalliperError>,
+    running: &mut Vec<RunningScenario<'s>>,
+) -> Result<Option<i32>, CalliperError> {
+    let mut pending = scenarios.iter().enumerate();
+    loop {
+        // Checked before spawning, as `on_event` may have taken a while.
+        if let Some(signal) = interrupts.received() {
+            return Ok(Some(signal));
+        }
+        while running.len() < parallelism {
+            let (position, (index, run)) = match pending.next() {
+                Some(next) => next,
+                None => break,
+            };
+            let config = default.clone().overwrite(run.config.clone());
+            let mut command = prepare_command(&run.command, &config, index.to_string());
+            let child = command.spawn()?;
+            let output = callgrind_output_name(
+                child.id(),
+                &config.get_output_file(),
+                config.get_cleanup_files(),
+            );
+            running.push(RunningScenario {
+                position,
+                scenario: run,
+                child,
+                output,
+                timeout: config.get_timeout(),
+                start: Instant::now(),
+            });
+            on_event(CallgrindEvent::Started(position))?;
+        }
+        if running.is_empty() {
+            return Ok(None);
+        }
+        let mut has_finished = false;
+        let mut i = 0;
+        while i < running.len() {
+            let current = &mut running[i];
+            let is_timed_out =
+                matches!(current.timeout, Some(timeout) if current.start.elapsed() >= timeout);
+            if let Some(status) = current.child.try_wait()? {
+                let mut current = running.remove(i);
+                has_finished = true;
+                let error = if !status.success() {
+                    Some(CalliperError::CallgrindFailed {
+                        name: current.scenario.name.clone(),
+                        status,
+                    })
+                } else if !Path::new(&current.output.path).exists() {
+                    Some(CalliperError::MissingOutput {
+                        name: current.scenario.name.clone(),
+                        path: current.output.path.clone(),
+                    })
+                } else {
+                    None
+                };
+                let (position, elapsed) = (current.position, current.start.elapsed());
+                let result = match error {
+                    Some(error) => {
+                        // Partial results are of no use to anyone.
+                        current.output.should_delete = true;
+                        Err(error)
+                    }
+                    None => Ok(current.output),
+                };
+                on_event(CallgrindEvent::Finished(position, elapsed, result))?;
+            } else if is_timed_out {
+                let mut current = running.remove(i);
+                kill_process_tree(&mut current.child)?;
+                // Partial results are of no use to anyone.
+                current.output.should_delete = true;
+                has_finished = true;
+                on_event(CallgrindEvent::Finished(
+                    current.position,
+                    current.start.elapsed(),
+                    Err(CalliperError::Timeout {
+                        name: current.scenario.name.clone(),
+                        timeout: current.timeout.unwrap_or_default(),
+                    }),
+                ))?;
+            } else {
+                i += 1;
+            }
+        }
+        if !has_finished {
+            on_event(CallgrindEvent::Waiting)?;
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Returns true if Valgrind executable at a given path can be spawned.
+pub(crate) fn is_valgrind_available(valgrind: &str) -> bool {
+    Command::new(valgrind)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
 }
 
 #[cfg(target_os = "freebsd")]
@@ -168,3 +483,135 @@ fn get_arch() -> String {
         .trim()
         .to_owned()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        callgrind_output_name, check_output_files, is_valgrind_available, shell_command,
+        spawn_callgrind, CallgrindEvent,
+    };
+    use crate::interrupt::InterruptHandler;
+    use crate::{CalliperError, Scenario, ScenarioConfig};
+    use std::process::Command;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn commands_are_quoted() {
+        let mut command = Command::new("/bin/bench");
+        command
+            .args(["--toggle-collect=search", "it's here", ""])
+            .env("CALLIPER_RUN_ID", "3")
+            .env_remove("HOME")
+            .current_dir("/tmp/a b");
+        assert_eq!(
+            shell_command(&command),
+            "cd '/tmp/a b' && env -u HOME CALLIPER_RUN_ID=3 /bin/bench --toggle-collect=search 'it'\\''s here' ''"
+        );
+    }
+
+    #[test]
+    fn missing_valgrind_is_not_available() {
+        assert!(!is_valgrind_available("calliper-nonexistent-valgrind"));
+    }
+
+    #[test]
+    fn only_generated_outputs_remove_other_files() {
+        let directory =
+            std::env::temp_dir().join(format!("calliper-outputs-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let user = directory.join("results");
+        for file in [
+            "results",
+            "results.1",
+            "callgrind.out.7",
+            "callgrind.out.7.1",
+        ] {
+            std::fs::write(directory.join(file), "").unwrap();
+        }
+        drop(callgrind_output_name(
+            7,
+            &Some(user.to_str().unwrap()),
+            true,
+        ));
+        assert!(!user.exists());
+        assert!(directory.join("results.1").exists());
+        let mut generated = callgrind_output_name(7, &None, true);
+        generated.path = directory
+            .join(&generated.path)
+            .to_string_lossy()
+            .into_owned();
+        drop(generated);
+        assert!(!directory.join("callgrind.out.7").exists());
+        assert!(!directory.join("callgrind.out.7.1").exists());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn output_files_can_not_be_shared() {
+        let scenario = |output: Option<&str>| {
+            let config = match output {
+                Some(output) => ScenarioConfig::default().output(output),
+                None => ScenarioConfig::default(),
+            };
+            Scenario::new_with_command(Command::new("true")).config(config)
+        };
+        let (a, b, c) = (scenario(Some("a")), scenario(Some("b")), scenario(None));
+        let default = ScenarioConfig::default();
+        assert!(check_output_files(&[(0, &a), (1, &b), (2, &c), (3, &c)], &default).is_ok());
+        assert!(check_output_files(&[(0, &a), (1, &a)], &default).is_err());
+        let default = ScenarioConfig::default().output("shared");
+        assert!(check_output_files(&[(0, &a), (1, &c)], &default).is_ok());
+        assert!(check_output_files(&[(0, &c), (1, &c)], &default).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn timed_out_scenarios_are_killed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory =
+            std::env::temp_dir().join(format!("calliper-timeout-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let marker = directory.join("survived");
+        // A stand-in for Valgrind that outlives the timeout, along with a child of it's own.
+        let valgrind = directory.join("valgrind");
+        std::fs::write(
+            &valgrind,
+            format!(
+                "#!/bin/sh\n(sleep 1 && touch '{}') &\nsleep 30\n",
+                marker.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&valgrind, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let config = ScenarioConfig::default()
+            .valgrind(valgrind.to_str().unwrap())
+            .aslr(true)
+            .timeout(Duration::from_millis(200));
+        let scenario = Scenario::new_with_command(Command::new("true")).config(config);
+        let mut results = vec![];
+        let start = Instant::now();
+        let signal = spawn_callgrind(
+            &[(0, &scenario)],
+            &ScenarioConfig::default(),
+            1,
+            &InterruptHandler::install().unwrap(),
+            |event| {
+                if let CallgrindEvent::Finished(_, _, result) = event {
+                    results.push(result);
+                }
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert_eq!(signal, None);
+        assert!(matches!(
+            results.as_slice(),
+            [Err(CalliperError::Timeout { .. })]
+        ));
+        std::thread::sleep(Duration::from_millis(1500));
+        assert!(!marker.exists());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,418 @@
+//! Parsing of bench binary command line arguments.
+//!
+//! Calliper accepts a subset of libtest options, so that `cargo bench -- <filter>` works the same
+//! way it does for libtest benchmarks. Other libtest options (e.g. `--nocapture` or
+//! `--test-threads`) are accepted and ignored, so that `cargo bench` invocations shared with libtest
+//! benchmarks don't fail.
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+use crate::error::CalliperError;
+use crate::metric::{Metric, UnknownMetric};
+use crate::scenario::Scenario;
+
+/// Defines how filters are matched against scenario names.
+#[derive(Clone, Debug)]
+enum Filter {
+    Substring(String),
+    Exact(String),
+    #[cfg(feature = "regex")]
+    Regex(Regex),
+}
+
+impl Filter {
+    fn is_match(&self, name: &str) -> bool {
+        match self {
+            Self::Substring(filter) => name.contains(filter.as_str()),
+            Self::Exact(filter) => name == filter,
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+/// Format of results printed by the default reporter, selected with `--format`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    /// Human readable tables (see [`TerminalReporter`](crate::TerminalReporter)).
+    #[default]
+    Pretty,
+    /// A JSON document (see [`JsonReporter`](crate::JsonReporter)).
+    Json,
+    /// CSV (see [`CsvReporter`](crate::CsvReporter)).
+    Csv,
+    /// JUnit XML (see [`JunitReporter`](crate::JunitReporter)).
+    Junit,
+    /// A Markdown summary (see [`MarkdownReporter`](crate::MarkdownReporter)).
+    Markdown,
+    /// Bencher Metric Format (see [`BencherReporter`](crate::BencherReporter)).
+    Bencher,
+    /// OpenMetrics text format (see [`OpenMetricsReporter`](crate::OpenMetricsReporter)).
+    OpenMetrics,
+    /// libtest-like `bench:` lines (see [`LibtestReporter`](crate::LibtestReporter)).
+    Libtest,
+}
+
+/// libtest options that don't take a value and have no meaning for Calliper.
+const IGNORED_LIBTEST_FLAGS: [&str; 12] = [
+    "--nocapture",
+    "--show-output",
+    "--quiet",
+    "-q",
+    "--ignored",
+    "--include-ignored",
+    "--exclude-should-panic",
+    "--force-run-in-process",
+    "--report-time",
+    "--ensure-time",
+    "--shuffle",
+    "--no-fail-fast",
+];
+/// libtest options that take a value and have no meaning for Calliper.
+const IGNORED_LIBTEST_OPTIONS: [&str; 5] = [
+    "--color",
+    "--test-threads",
+    "--logfile",
+    "--shuffle-seed",
+    "-Z",
+];
+/// Formats accepted by libtest's `--format` that have no Calliper counterpart. Default format is
+/// used in their place.
+const IGNORED_LIBTEST_FORMATS: [&str; 1] = ["terse"];
+
+impl core::str::FromStr for OutputFormat {
+    type Err = CalliperError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pretty" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "junit" => Ok(Self::Junit),
+            "markdown" => Ok(Self::Markdown),
+            "bencher" => Ok(Self::Bencher),
+            "openmetrics" => Ok(Self::OpenMetrics),
+            "libtest" => Ok(Self::Libtest),
+            _ => Err(CalliperError::InvalidArguments(format!(
+                "unknown output format `{}` (expected `pretty`, `json`, `csv`, `junit`, `markdown`, `bencher`, `openmetrics` or `libtest`)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Command line arguments of a bench binary.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Arguments {
+    filters: Vec<Filter>,
+    skip: Vec<Filter>,
+    /// If set, names of selected scenarios are printed instead of running them.
+    pub(crate) list: bool,
+    /// If set, commands running selected scenarios under Callgrind are printed instead of running
+    /// them.
+    pub(crate) dry_run: bool,
+    bench: bool,
+    test: bool,
+    /// Name under which results should be saved as a baseline.
+    pub(crate) save_baseline: Option<String>,
+    /// Name of a baseline to compare results against.
+    pub(crate) baseline: Option<String>,
+    /// If set, results are appended to run history.
+    pub(crate) record_history: bool,
+    /// Path of HTML report of run history.
+    pub(crate) html_report: Option<String>,
+    /// Directory with Callgrind outputs to build reports from, instead of running Callgrind.
+    pub(crate) replay: Option<String>,
+    /// If set, a breakdown of all events is printed for each scenario.
+    pub(crate) verbose: bool,
+    /// Format of results printed by the default reporter.
+    pub(crate) format: OutputFormat,
+    /// Metric used by reporters that show a single metric.
+    pub(crate) metric: Option<Metric>,
+    /// If set, results are also written to Criterion directory.
+    pub(crate) criterion: bool,
+    /// Options that were not recognized and were ignored.
+    pub(crate) unrecognized: Vec<String>,
+}
+
+impl Arguments {
+    /// Parses arguments of current process.
+    pub(crate) fn from_env() -> Result<Self, CalliperError> {
+        let args = Self::parse(std::env::args().skip(1))?;
+        for option in &args.unrecognized {
+            eprintln!("warning: unrecognized option `{}` was ignored", option);
+        }
+        Ok(args)
+    }
+
+    pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CalliperError> {
+        let mut filters = vec![];
+        let mut skip = vec![];
+        let mut exact = false;
+        let mut is_regex = false;
+        let mut list = false;
+        let mut dry_run = false;
+        let mut bench = false;
+        let mut test = false;
+        let mut save_baseline = None;
+        let mut baseline = None;
+        let mut record_history = false;
+        let mut html_report = None;
+        let mut replay = None;
+        let mut verbose = false;
+        let mut format = OutputFormat::default();
+        let mut metric = None;
+        let mut criterion = false;
+        let mut unrecognized = vec![];
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if arg.starts_with("--") => (flag.to_owned(), Some(value)),
+                _ => (arg.clone(), None),
+            };
+            let mut value = |flag: &str| {
+                inline_value
+                    .map(str::to_owned)
+                    .or_else(|| args.next())
+                    .ok_or_else(|| {
+                        CalliperError::InvalidArguments(format!("`{}` requires a value", flag))
+                    })
+            };
+            match flag.as_str() {
+                // Passed in by `cargo bench`.
+                "--bench" => bench = true,
+                // Forces test mode even under `cargo bench`.
+                "--test" => test = true,
+                "--exact" => exact = true,
+                "--regex" => is_regex = true,
+                "--list" => list = true,
+                "--dry-run" => dry_run = true,
+                "--skip" => skip.push(value(&flag)?),
+                "--save-baseline" => save_baseline = Some(value(&flag)?),
+                "--baseline" => baseline = Some(value(&flag)?),
+                "--record-history" => record_history = true,
+                "--html-report" => html_report = Some(value(&flag)?),
+                "--replay" => replay = Some(value(&flag)?),
+                "--verbose" | "-v" => verbose = true,
+                "--format" => {
+                    let value = value(&flag)?;
+                    if !IGNORED_LIBTEST_FORMATS.contains(&value.as_str()) {
+                        format = value.parse()?;
+                    }
+                }
+                "--criterion" => criterion = true,
+                "--metric" => {
+                    metric = Some(value(&flag)?.parse().map_err(|e: UnknownMetric| {
+                        CalliperError::InvalidArguments(e.to_string())
+                    })?)
+                }
+                _ if IGNORED_LIBTEST_FLAGS.contains(&flag.as_str()) => {}
+                _ if IGNORED_LIBTEST_OPTIONS.contains(&flag.as_str()) => {
+                    value(&flag)?;
+                }
+                _ if flag.starts_with('-') => unrecognized.push(arg),
+                _ => filters.push(arg),
+            }
+        }
+        let to_filter = |filter: String| -> Result<Filter, CalliperError> {
+            if is_regex {
+                #[cfg(feature = "regex")]
+                return Regex::new(&filter)
+                    .map(Filter::Regex)
+                    .map_err(|e| CalliperError::InvalidArguments(e.to_string()));
+                #[cfg(not(feature = "regex"))]
+                return Err(CalliperError::InvalidArguments(
+                    "`--regex` requires `regex` feature of calliper".to_owned(),
+                ));
+            }
+            if exact {
+                Ok(Filter::Exact(filter))
+            } else {
+                Ok(Filter::Substring(filter))
+            }
+        };
+        Ok(Self {
+            filters: filters
+                .into_iter()
+                .map(to_filter)
+                .collect::<Result<_, _>>()?,
+            skip: skip.into_iter().map(to_filter).collect::<Result<_, _>>()?,
+            list,
+            dry_run,
+            bench,
+            test,
+            save_baseline,
+            baseline,
+            record_history,
+            html_report,
+            replay,
+            verbose,
+            format,
+            metric,
+            criterion,
+            unrecognized,
+        })
+    }
+
+    /// Returns true if scenarios should be run natively (without Callgrind) just to check that
+    /// they work.
+    ///
+    /// `cargo bench` passes `--bench` flag to bench binaries, while `cargo test --benches` does
+    /// not. `--test` forces test mode in both cases.
+    pub(crate) fn is_test_mode(&self) -> bool {
+        self.test || !self.bench
+    }
+
+    /// Returns true if scenario should be run according to filters.
+    pub(crate) fn is_selected(&self, scenario: &Scenario) -> bool {
+        (self.filters.is_empty() || self.filters.iter().any(|f| f.is_match(&scenario.name)))
+            && !self.skip.iter().any(|f| f.is_match(&scenario.name))
+    }
+
+    /// Prints selected scenarios in a format compatible with libtest's `--list`.
+    pub(crate) fn print_list<'a>(&self, scenarios: impl IntoIterator<Item = &'a Scenario>) {
+        print!("{}", self.format_list(scenarios));
+    }
+
+    /// Lists scenarios as benchmarks or, in test mode (where they're run natively), as tests.
+    fn format_list<'a>(&self, scenarios: impl IntoIterator<Item = &'a Scenario>) -> String {
+        let kind = if self.is_test_mode() {
+            "test"
+        } else {
+            "benchmark"
+        };
+        let mut out = String::new();
+        let mut count = 0;
+        for scenario in scenarios {
+            out.push_str(&format!("{}: {}\n", scenario.name, kind));
+            count += 1;
+        }
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+        let (tests, benchmarks) = if self.is_test_mode() {
+            (count, 0)
+        } else {
+            (0, count)
+        };
+        out.push_str(&format!(
+            "\n{} test{}, {} benchmark{}\n",
+            tests,
+            plural(tests),
+            benchmarks,
+            plural(benchmarks)
+        ));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Arguments, OutputFormat};
+    use crate::Scenario;
+
+    fn scenario(name: &str) -> Scenario {
+        Scenario::new_with_command(std::process::Command::new("true")).name(name)
+    }
+
+    fn parse(args: &[&str]) -> Arguments {
+        Arguments::parse(args.iter().map(|s| s.to_string())).unwrap()
+    }
+
+    #[test]
+    fn no_filters_select_everything() {
+        let args = parse(&["--bench"]);
+        assert!(args.is_selected(&scenario("linear_search")));
+        assert!(!args.list);
+    }
+
+    #[test]
+    fn test_mode_is_used_unless_benchmarking() {
+        assert!(parse(&[]).is_test_mode());
+        assert!(parse(&["--test"]).is_test_mode());
+        assert!(parse(&["--bench", "--test"]).is_test_mode());
+        assert!(!parse(&["--bench"]).is_test_mode());
+    }
+
+    #[test]
+    fn scenarios_are_listed_as_tests_in_test_mode() {
+        let scenarios = [scenario("a"), scenario("b")];
+        assert_eq!(
+            parse(&["--bench"]).format_list(&scenarios),
+            "a: benchmark\nb: benchmark\n\n0 tests, 2 benchmarks\n"
+        );
+        assert_eq!(
+            parse(&[]).format_list(&scenarios[..1]),
+            "a: test\n\n1 test, 0 benchmarks\n"
+        );
+    }
+
+    #[test]
+    fn filters_match_substrings() {
+        let args = parse(&["--bench", "search"]);
+        assert!(args.is_selected(&scenario("linear_search")));
+        assert!(!args.is_selected(&scenario("fibonacci")));
+    }
+
+    #[test]
+    fn exact_filters_match_whole_names() {
+        let args = parse(&["--exact", "search"]);
+        assert!(!args.is_selected(&scenario("linear_search")));
+        assert!(args.is_selected(&scenario("search")));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_filters_are_supported() {
+        let args = parse(&["--regex", "^(linear|binary)_"]);
+        assert!(args.is_selected(&scenario("binary_search")));
+        assert!(!args.is_selected(&scenario("search_binary")));
+    }
+
+    #[test]
+    fn skipped_scenarios_are_not_selected() {
+        let args = parse(&["search", "--skip", "binary", "--skip=linear"]);
+        assert!(!args.is_selected(&scenario("binary_search")));
+        assert!(!args.is_selected(&scenario("linear_search")));
+        assert!(args.is_selected(&scenario("interpolation_search")));
+    }
+
+    #[test]
+    fn baseline_names_are_parsed() {
+        let args = parse(&["--bench", "--save-baseline", "new", "--baseline=main"]);
+        assert_eq!(args.save_baseline, Some("new".to_owned()));
+        assert_eq!(args.baseline, Some("main".to_owned()));
+        assert_eq!(parse(&["--bench"]).save_baseline, None);
+    }
+
+    #[test]
+    fn libtest_options_are_ignored() {
+        let args = parse(&[
+            "--bench",
+            "--nocapture",
+            "--color",
+            "never",
+            "--test-threads=1",
+            "-q",
+            "--format",
+            "terse",
+            "search",
+        ]);
+        assert!(args.unrecognized.is_empty());
+        assert_eq!(args.format, OutputFormat::Pretty);
+        assert!(args.is_selected(&scenario("linear_search")));
+        assert!(!args.is_selected(&scenario("fibonacci")));
+    }
+
+    #[test]
+    fn unknown_options_are_reported() {
+        let args = parse(&["--frobnicate", "search"]);
+        assert_eq!(args.unrecognized, ["--frobnicate"]);
+        assert!(args.is_selected(&scenario("search")));
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        assert!(Arguments::parse(["--skip".to_string()]).is_err());
+        assert!(Arguments::parse(["--format=xml".to_string()]).is_err());
+        assert!(Arguments::parse(["--metric=instructions".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+use crate::metric::Metric;
+use crate::parser::ParsedCallgrindOutput;
+use crate::runner::Report;
+use crate::style::{group_digits, paint, Color};
+
+/// Change of a single metric between two runs of a scenario.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Delta {
+    /// Compared metric.
+    pub metric: Metric,
+    /// Previous value of a metric.
+    pub baseline: u64,
+    /// New value of a metric.
+    pub current: u64,
+}
+
+impl Delta {
+    /// Returns absolute change of a metric. Positive value means that the metric increased.
+    pub fn difference(&self) -> i128 {
+        i128::from(self.current) - i128::from(self.baseline)
+    }
+    /// Returns change of a metric in percent of it's previous value, or `None` if the previous
+    /// value was zero.
+    pub fn percentage(&self) -> Option<f64> {
+        if self.baseline == 0 {
+            None
+        } else {
+            Some(self.difference() as f64 * 100.0 / self.baseline as f64)
+        }
+    }
+    /// Returns ratio of new value to the previous value, or `None` if the previous value was zero.
+    pub fn ratio(&self) -> Option<f64> {
+        if self.baseline == 0 {
+            None
+        } else {
+            Some(self.current as f64 / self.baseline as f64)
+        }
+    }
+    /// Returns true if the metric increased. As all Callgrind metrics are costs, an increase is a
+    /// regression.
+    pub fn is_regression(&self) -> bool {
+        self.current > self.baseline
+    }
+    /// Returns true if the metric decreased.
+    pub fn is_improvement(&self) -> bool {
+        self.current < self.baseline
+    }
+    /// Describes the change, e.g. `instruction_reads: 100 -> 110 (+10, +10.00%)`. Unless
+    /// `is_colored` is false, the change is colored in terminal output.
+    pub(crate) fn describe(&self, is_colored: bool) -> String {
+        let mut change = format!("{:+}", self.difference());
+        if let Some(percentage) = self.percentage() {
+            change = format!("{}, {:+.2}%", change, percentage);
+        }
+        let change = if !is_colored {
+            change
+        } else if self.is_regression() {
+            paint(change, Color::Red)
+        } else if self.is_improvement() {
+            paint(change, Color::Green)
+        } else {
+            change
+        };
+        format!(
+            "{}: {} -> {} ({})",
+            self.metric, self.baseline, self.current, change
+        )
+    }
+}
+
+impl core::fmt::Display for Delta {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(fmt, "{}", self.describe(false))
+    }
+}
+
+/// Comparison of scenario results against a baseline.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comparison {
+    /// Results of a previous run.
+    pub baseline: ParsedCallgrindOutput,
+    /// Results of a current run.
+    pub current: ParsedCallgrindOutput,
+}
+
+impl Comparison {
+    /// Returns changes of all metrics that are available in both runs.
+    pub fn deltas(&self) -> Vec<Delta> {
+        Metric::ALL
+            .iter()
+            .filter_map(|&metric| self.delta(metric))
+            .collect()
+    }
+    /// Returns change of a given metric, if it's available in both runs.
+    pub fn delta(&self, metric: Metric) -> Option<Delta> {
+        Some(Delta {
+            metric,
+            baseline: self.baseline.get(metric)?,
+            current: self.current.get(metric)?,
+        })
+    }
+}
+
+impl core::fmt::Display for Comparison {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(fmt, "{}", self.current.name())?;
+        for delta in self.deltas() {
+            write!(fmt, "\n    {}", delta)?;
+        }
+        Ok(())
+    }
+}
+
+/// Metrics that are shown in a [`ComparisonTable`].
+const TABLE_METRICS: [Metric; 2] = [Metric::InstructionReads, Metric::Cycles];
+
+/// Results of scenarios relative to a [reference scenario](crate::Scenario::reference) of their
+/// group, formatted as a table. Groups without a reference scenario are omitted.
+/// ```no_run
+/// # use calliper::{ComparisonTable, Runner, Scenario};
+/// # fn linear_search() {}
+/// # fn binary_search() {}
+/// let benches = [
+///     Scenario::new(linear_search).reference(),
+///     Scenario::new(binary_search),
+/// ];
+/// if let Some(results) = Runner::default().run(&benches).unwrap() {
+///     let reports: Vec<_> = results.into_iter().flatten().collect();
+///     println!("{}", ComparisonTable::new(&reports));
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComparisonTable {
+    groups: Vec<TableGroup>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct TableGroup {
+    name: Option<String>,
+    reference: String,
+    rows: Vec<TableRow>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct TableRow {
+    name: String,
+    results: ParsedCallgrindOutput,
+    relative: Option<Comparison>,
+}
+
+impl ComparisonTable {
+    /// Creates a table out of scenario reports.
+    pub fn new<'r, 'a: 'r>(reports: impl IntoIterator<Item = &'r Report<'a>>) -> Self {
+        let reports: Vec<&Report> = reports.into_iter().collect();
+        let mut groups: Vec<TableGroup> = vec![];
+        for reference in reports.iter().filter(|report| report.is_reference()) {
+            if groups
+                .iter()
+                .any(|group| group.name.as_deref() == reference.group())
+            {
+                continue;
+            }
+            let rows = reports
+                .iter()
+                .filter(|report| report.group() == reference.group())
+                .map(|report| TableRow {
+                    name: report.name().to_owned(),
+                    results: report.parse(),
+                    relative: report.relative_comparison(),
+                })
+                .collect();
+            groups.push(TableGroup {
+                name: reference.group().map(str::to_owned),
+                reference: reference.name().to_owned(),
+                rows,
+            });
+        }
+        Self { groups }
+    }
+    /// Returns true if there are no groups with a reference scenario.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+impl core::fmt::Display for ComparisonTable {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (index, group) in self.groups.iter().enumerate() {
+            if index > 0 {
+                writeln!(fmt)?;
+            }
+            match &group.name {
+                Some(name) => writeln!(fmt, "{} (reference: {})", name, group.reference)?,
+                None => writeln!(fmt, "reference: {}", group.reference)?,
+            }
+            let metrics: Vec<Metric> = TABLE_METRICS
+                .iter()
+                .copied()
+                .filter(|&metric| {
+                    group
+                        .rows
+                        .iter()
+                        .all(|row| row.results.get(metric).is_some())
+                })
+                .collect();
+            let mut header = vec!["scenario".to_owned()];
+            for metric in &metrics {
+                header.extend([
+                    metric.to_string(),
+                    "ratio".to_owned(),
+                    "difference".to_owned(),
+                ]);
+            }
+            let mut cells = vec![header];
+            for row in &group.rows {
+                let mut line = vec![row.name.clone()];
+                for &metric in &metrics {
+                    // Checked above.
+                    let value = row.results.get(metric).unwrap_or_default();
+                    line.push(group_digits(value));
+                    match row.relative.as_ref().and_then(|r| r.delta(metric)) {
+                        Some(delta) => {
+                            line.push(
+                                delta
+                                    .ratio()
+                                    .map_or_else(|| "-".to_owned(), |r| format!("{:.2}x", r)),
+                            );
+                            line.push(signed(delta.difference()));
+                        }
+                        None => line.extend(["1.00x".to_owned(), "-".to_owned()]),
+                    }
+                }
+                cells.push(line);
+            }
+            let widths: Vec<usize> = (0..cells[0].len())
+                .map(|column| {
+                    cells
+                        .iter()
+                        .map(|line| line[column].len())
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+            for line in cells {
+                let mut text = String::new();
+                for (column, (cell, width)) in line.iter().zip(&widths).enumerate() {
+                    if column == 0 {
+                        text.push_str(&format!("    {:<width$}", cell, width = width));
+                    } else {
+                        text.push_str(&format!("  {:>width$}", cell, width = width));
+                    }
+                }
+                writeln!(fmt, "{}", text.trim_end())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats a change with a sign and thousands separators, e.g. `+1,234`.
+pub(crate) fn signed(difference: i128) -> String {
+    if difference >= 0 {
+        format!("+{}", group_digits(difference))
+    } else {
+        group_digits(difference)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Delta;
+    use crate::Metric;
+
+    fn delta(baseline: u64, current: u64) -> Delta {
+        Delta {
+            metric: Metric::InstructionReads,
+            baseline,
+            current,
+        }
+    }
+
+    #[test]
+    fn changes_are_computed_relative_to_baseline() {
+        assert_eq!(delta(200, 250).difference(), 50);
+        assert_eq!(delta(200, 250).percentage(), Some(25.0));
+        assert_eq!(delta(200, 150).difference(), -50);
+        assert_eq!(delta(200, 150).percentage(), Some(-25.0));
+        assert_eq!(delta(0, 10).percentage(), None);
+    }
+
+    #[test]
+    fn ratio_is_relative_to_baseline() {
+        assert_eq!(delta(200, 50).ratio(), Some(0.25));
+        assert_eq!(delta(0, 50).ratio(), None);
+    }
+
+    #[test]
+    fn increases_are_regressions() {
+        assert!(delta(1, 2).is_regression());
+        assert!(delta(2, 1).is_improvement());
+        assert!(!delta(2, 2).is_regression() && !delta(2, 2).is_improvement());
+    }
+}
@@ -1,4 +1,6 @@
 #![allow(unused)]
+use std::time::Duration;
+
 use super::utils;
 
 /// Callgrind execution settings.
@@ -16,6 +18,7 @@ pub struct ScenarioConfig {
     pub(crate) collect_bus: Option<bool>,
     pub(crate) filters: Option<Vec<String>>,
     pub(crate) output_file: Option<Option<String>>,
+    pub(crate) timeout: Option<Duration>,
 }
 
 impl ScenarioConfig {
@@ -101,10 +104,22 @@ impl ScenarioConfig {
     /// Sets callgrind file output path.
     /// Defaults to `callgrind.out.{pid}`, where pid is - naturally - not up to us anyhow. If you
     /// intend to process Callgrind's results further, it is recommended to set the path manually.
+    ///
+    /// With [parallelism](crate::Runner::parallelism), each scenario needs an output file of it's
+    /// own, so this should not be set in runner configuration.
     pub fn output(mut self, path: impl Into<String>) -> Self {
         self.output_file = Some(Some(path.into()));
         self
     }
+    /// Sets wall-clock time limit for a scenario. When it expires, Callgrind process (along with
+    /// all of it's children) is killed, it's partial output is removed and
+    /// [`CalliperError::Timeout`](crate::CalliperError::Timeout) is reported for that scenario.
+    /// Remaining scenarios are not affected.
+    /// Defaults to no time limit.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
     /// Returns a path to valgrind.
     pub fn get_valgrind(&self) -> &str {
         if let Some(v) = &self.valgrind_path {
@@ -140,6 +155,10 @@ impl ScenarioConfig {
     pub fn get_filters(&self) -> &[String] {
         self.filters.as_deref().unwrap_or(&[])
     }
+    /// Returns wall-clock time limit of a scenario, if there's any.
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
     pub(crate) fn overwrite(self, other: Self) -> Self {
         Self {
             branch_sim: other.branch_sim.or(self.branch_sim),
@@ -150,6 +169,7 @@ impl ScenarioConfig {
             cache: other.cache.or(self.cache),
             filters: other.filters.or(self.filters),
             output_file: other.output_file.or(self.output_file),
+            timeout: other.timeout.or(self.timeout),
         }
     }
 }
@@ -176,6 +196,34 @@ pub struct CacheOptions {
     pub last_level: Option<CacheParameters>,
 }
 
+/// Formats cache options the way they're passed to Callgrind, e.g.
+/// `D1=32768,8,64 I1=32768,8,64 LL=8388608,16,64`. Levels without explicit parameters are omitted;
+/// if there are none, `native` is printed.
+impl core::fmt::Display for CacheOptions {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let levels: Vec<String> = [
+            ("D1", &self.first_level_data),
+            ("I1", &self.first_level_code),
+            ("LL", &self.last_level),
+        ]
+        .iter()
+        .filter_map(|(prefix, params)| {
+            params.as_ref().map(|params| {
+                format!(
+                    "{}={},{},{}",
+                    prefix, params.size, params.associativity, params.line_size
+                )
+            })
+        })
+        .collect();
+        if levels.is_empty() {
+            write!(fmt, "native")
+        } else {
+            write!(fmt, "{}", levels.join(" "))
+        }
+    }
+}
+
 /// Size, associativity and line size options for each
 /// simulated cache level.
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, serde::Serialize, serde::Deserialize)]
@@ -1,5 +1,8 @@
+use std::time::Duration;
+
 use thiserror::Error;
 
+use crate::policy::Regression;
 use crate::utils;
 
 /// An error that occured during benchmark harness.
@@ -19,6 +22,66 @@ pub enum CalliperError {
     /// circumstances, unless Calliper environment variable is somehow overwritten.
     #[error("Internal error: run ID is malformed. Please report this to Calliper bug tracker")]
     RunIdError(#[from] utils::RunIdError),
+    /// Valgrind executable could not be found. Calliper reports this as an error only when a
+    /// missing Valgrind is explicitly disallowed (see [`Runner::require_valgrind`]).
+    ///
+    /// [`Runner::require_valgrind`]: crate::Runner::require_valgrind
+    #[error("Valgrind executable `{path}` could not be found or executed")]
+    ValgrindNotFound {
+        /// Path to Valgrind executable.
+        path: String,
+    },
+    /// Scenario did not finish within time limit set with
+    /// [`ScenarioConfig::timeout`](crate::ScenarioConfig::timeout).
+    #[error("Scenario `{name}` timed out after {timeout:?}")]
+    Timeout {
+        /// Name of the scenario that timed out.
+        name: String,
+        /// Time limit of the scenario.
+        timeout: Duration,
+    },
+    /// Callgrind process of a scenario exited unsuccessfully, e.g. because the benchmarked function
+    /// panicked.
+    #[error("Callgrind run of scenario `{name}` failed ({status})")]
+    CallgrindFailed {
+        /// Name of the failed scenario.
+        name: String,
+        /// Exit status of Callgrind process.
+        status: std::process::ExitStatus,
+    },
+    /// Callgrind process of a scenario exited, but it's output file could not be found.
+    #[error("Callgrind output `{path}` of scenario `{name}` does not exist")]
+    MissingOutput {
+        /// Name of the scenario.
+        name: String,
+        /// Expected path of Callgrind output file.
+        path: String,
+    },
+    /// Multiple scenarios that could run in parallel were configured to write Callgrind output to
+    /// the same file (see [`ScenarioConfig::output`](crate::ScenarioConfig::output)).
+    #[error("Callgrind output file `{path}` is shared by multiple scenarios, which can't run in parallel")]
+    SharedOutputFile {
+        /// Path of the shared output file.
+        path: String,
+    },
+    /// Bench binary was invoked with malformed command line arguments.
+    #[error("Invalid command line arguments: {0}")]
+    InvalidArguments(String),
+    /// Some scenarios panicked when run natively in test mode (e.g. with `cargo test --benches`).
+    #[error("{} scenario(s) failed: {}", names.len(), names.join(", "))]
+    ScenariosFailed {
+        /// Names of failed scenarios.
+        names: Vec<String>,
+    },
+    /// I/O error, e.g. while storing results.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Some scenarios breached their [regression policies](crate::RegressionPolicy).
+    #[error("{} performance regression(s) detected:{}", regressions.len(), format_regressions(regressions))]
+    RegressionsDetected {
+        /// All detected regressions.
+        regressions: Vec<Regression>,
+    },
     /// Generic benchmark error. Insufficient privileges are one of the most common causes.
     #[error("Benchmark failure: {reason}")]
     BenchmarkFailure {
@@ -27,3 +90,10 @@ pub enum CalliperError {
         reason: Box<dyn std::error::Error>,
     },
 }
+
+fn format_regressions(regressions: &[Regression]) -> String {
+    regressions
+        .iter()
+        .map(|regression| format!("\n    {}", regression.describe(false)))
+        .collect()
+}
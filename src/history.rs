@@ -0,0 +1,185 @@
+//! Append-only history of benchmark runs.
+//!
+//! History of a bench binary is stored in `target/calliper/<bench>/history.jsonl`, with one JSON
+//! record per line for each scenario of each run.
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::metric::Metric;
+use crate::parser::ParsedCallgrindOutput;
+use crate::utils;
+
+/// Results of a single scenario in a single run.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct HistoryRecord {
+    /// Time of the run, in seconds since Unix epoch.
+    pub timestamp: u64,
+    /// Git commit that was checked out during the run, if it could be determined.
+    pub commit: Option<String>,
+    /// Name of the bench binary.
+    pub bench: String,
+    /// Name of the scenario.
+    pub scenario: String,
+    /// Parsed Callgrind results of the scenario.
+    pub results: ParsedCallgrindOutput,
+}
+
+/// A value of a metric in a single run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DataPoint {
+    /// Time of the run, in seconds since Unix epoch.
+    pub timestamp: u64,
+    /// Git commit that was checked out during the run, if it could be determined.
+    pub commit: Option<String>,
+    /// Value of a metric.
+    pub value: u64,
+}
+
+/// History of benchmark runs stored in a JSON lines file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    /// Opens history stored at a given path. The file does not have to exist.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+    /// Opens history of a given bench binary, stored at `target/calliper/<bench>/history.jsonl`.
+    pub fn of_bench(bench: &str) -> Self {
+        Self::open(
+            utils::calliper_dir()
+                .join(utils::sanitize_file_name(bench))
+                .join("history.jsonl"),
+        )
+    }
+    /// Returns path of a history file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Appends records at the end of history.
+    pub fn append<'a>(
+        &self,
+        records: impl IntoIterator<Item = &'a HistoryRecord>,
+    ) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        for record in records {
+            contents.push_str(&serde_json::to_string(record)?);
+            contents.push('\n');
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(contents.as_bytes())
+    }
+    /// Returns all records, from the oldest to the newest. History that was not recorded yet is
+    /// empty.
+    pub fn records(&self) -> io::Result<Vec<HistoryRecord>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+        let mut records = vec![];
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                records.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(records)
+    }
+    /// Returns names of all scenarios present in history, in order of their first appearance.
+    pub fn scenarios(&self) -> io::Result<Vec<String>> {
+        let mut scenarios: Vec<String> = vec![];
+        for record in self.records()? {
+            if !scenarios.contains(&record.scenario) {
+                scenarios.push(record.scenario);
+            }
+        }
+        Ok(scenarios)
+    }
+    /// Returns values of a metric of a given scenario over time, from the oldest to the newest.
+    /// Runs in which the metric was not collected are skipped.
+    pub fn series(&self, scenario: &str, metric: Metric) -> io::Result<Vec<DataPoint>> {
+        Ok(Self::series_of(&self.records()?, scenario, metric))
+    }
+    /// Same as [`Self::series`], but for records that were already read.
+    pub(crate) fn series_of(
+        records: &[HistoryRecord],
+        scenario: &str,
+        metric: Metric,
+    ) -> Vec<DataPoint> {
+        records
+            .iter()
+            .filter(|record| record.scenario == scenario)
+            .filter_map(|record| {
+                Some(DataPoint {
+                    value: record.results.get(metric)?,
+                    timestamp: record.timestamp,
+                    commit: record.commit.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataPoint, History, HistoryRecord};
+    use crate::parser::ParsedCallgrindOutput;
+    use crate::Metric;
+
+    fn record(timestamp: u64, scenario: &str, instruction_reads: u64) -> HistoryRecord {
+        let results = ParsedCallgrindOutput::with_events(
+            scenario,
+            &[(Metric::InstructionReads, instruction_reads)],
+        );
+        HistoryRecord {
+            timestamp,
+            commit: Some(format!("commit{}", timestamp)),
+            bench: "bench".to_owned(),
+            scenario: scenario.to_owned(),
+            results,
+        }
+    }
+
+    #[test]
+    fn series_are_queried_from_appended_records() {
+        let path = std::env::temp_dir().join(format!(
+            "calliper-history-test-{}.jsonl",
+            std::process::id()
+        ));
+        let history = History::open(&path);
+        assert!(history.records().unwrap().is_empty());
+        history
+            .append(&[record(1, "foo", 10), record(1, "bar", 20)])
+            .unwrap();
+        history.append(&[record(2, "foo", 15)]).unwrap();
+
+        assert_eq!(history.scenarios().unwrap(), ["foo", "bar"]);
+        assert_eq!(
+            history.series("foo", Metric::InstructionReads).unwrap(),
+            [
+                DataPoint {
+                    timestamp: 1,
+                    commit: Some("commit1".to_owned()),
+                    value: 10
+                },
+                DataPoint {
+                    timestamp: 2,
+                    commit: Some("commit2".to_owned()),
+                    value: 15
+                }
+            ]
+        );
+        assert!(history.series("foo", Metric::Cycles).unwrap().is_empty());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,319 @@
+//! Self-contained HTML report of run history, with inline SVG charts and no external resources.
+use core::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+use crate::history::{DataPoint, History, HistoryRecord};
+use crate::metric::Metric;
+use crate::utils::{self, escape};
+
+/// Metrics that are charted in HTML report.
+const CHARTED_METRICS: [Metric; 2] = [Metric::InstructionReads, Metric::Cycles];
+
+const CHART_WIDTH: f64 = 640.0;
+const CHART_HEIGHT: f64 = 220.0;
+const CHART_MARGIN: f64 = 40.0;
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: right; }
+th { background: #eee; cursor: pointer; user-select: none; }
+td:first-child, th:first-child { text-align: left; }
+.regression { color: #b00; font-weight: bold; }
+.improvement { color: #080; }
+.charts { display: flex; flex-wrap: wrap; gap: 1em; }
+svg { border: 1px solid #ddd; background: #fafafa; }
+svg polyline { fill: none; stroke: #36c; stroke-width: 2; }
+svg circle { fill: #36c; }
+svg circle.regression { fill: #b00; }
+svg text { font-size: 11px; fill: #555; }
+"#;
+
+/// Sorts table by clicked column. Numeric columns are sorted by `data-value` attribute.
+const SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable th").forEach(function (th, column) {
+  th.addEventListener("click", function () {
+    var body = th.closest("table").tBodies[0];
+    var ascending = th.dataset.order !== "asc";
+    th.dataset.order = ascending ? "asc" : "desc";
+    var key = function (row) {
+      var cell = row.cells[column];
+      return cell.dataset.value !== undefined ? parseFloat(cell.dataset.value) : cell.textContent;
+    };
+    Array.from(body.rows)
+      .sort(function (a, b) {
+        var x = key(a), y = key(b);
+        var order = x < y ? -1 : x > y ? 1 : 0;
+        return ascending ? order : -order;
+      })
+      .forEach(function (row) { body.appendChild(row); });
+  });
+});
+"#;
+
+/// Renders run history as a single, offline HTML page.
+///
+/// The page contains a sortable table with the latest values of each scenario and charts of
+/// instruction count and estimated cycles over time. Increases relative to a previous run are
+/// highlighted as regressions.
+/// ```no_run
+/// use calliper::{History, HtmlReport};
+///
+/// let history = History::of_bench("fibonacci");
+/// HtmlReport::new(&history).write("fibonacci.html").unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct HtmlReport<'a> {
+    history: &'a History,
+    title: String,
+}
+
+impl<'a> HtmlReport<'a> {
+    /// Creates a report of a given history.
+    pub fn new(history: &'a History) -> Self {
+        Self {
+            history,
+            title: "Calliper benchmark history".to_owned(),
+        }
+    }
+    /// Overrides page title.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+    /// Writes the report to a file.
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.render()?)
+    }
+    /// Renders the report.
+    pub fn render(&self) -> io::Result<String> {
+        let records = self.history.records()?;
+        let scenarios = self.history.scenarios()?;
+        utils::render_string(|out| self.render_into(out, &records, &scenarios))
+    }
+
+    fn render_into(
+        &self,
+        out: &mut String,
+        records: &[HistoryRecord],
+        scenarios: &[String],
+    ) -> core::fmt::Result {
+        let title = escape(&self.title);
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(out, "<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+        writeln!(out, "<title>{}</title>\n<style>{}</style>", title, STYLE)?;
+        writeln!(out, "</head>\n<body>\n<h1>{}</h1>", title)?;
+        if records.is_empty() {
+            writeln!(out, "<p>No runs recorded yet.</p>")?;
+        } else {
+            Self::render_summary(out, records, scenarios)?;
+            for scenario in scenarios {
+                writeln!(out, "<h2>{}</h2>\n<div class=\"charts\">", escape(scenario))?;
+                for metric in CHARTED_METRICS {
+                    let points = History::series_of(records, scenario, metric);
+                    if !points.is_empty() {
+                        render_chart(out, metric, &points)?;
+                    }
+                }
+                writeln!(out, "</div>")?;
+            }
+        }
+        writeln!(out, "<script>{}</script>\n</body>\n</html>", SCRIPT)
+    }
+
+    fn render_summary(
+        out: &mut String,
+        records: &[HistoryRecord],
+        scenarios: &[String],
+    ) -> core::fmt::Result {
+        writeln!(out, "<h2>Latest results</h2>")?;
+        writeln!(
+            out,
+            "<table class=\"sortable\">\n<thead><tr><th>Scenario</th><th>Commit</th>"
+        )?;
+        for metric in CHARTED_METRICS {
+            writeln!(out, "<th>{}</th><th>Change</th>", metric)?;
+        }
+        writeln!(out, "</tr></thead>\n<tbody>")?;
+        for scenario in scenarios {
+            let latest = match records.iter().rev().find(|r| &r.scenario == scenario) {
+                Some(latest) => latest,
+                None => continue,
+            };
+            writeln!(
+                out,
+                "<tr><td>{}</td><td>{}</td>",
+                escape(scenario),
+                label(latest.commit.as_deref(), latest.timestamp)
+            )?;
+            for metric in CHARTED_METRICS {
+                let points = History::series_of(records, scenario, metric);
+                match points.as_slice() {
+                    [] => write!(out, "<td></td><td></td>")?,
+                    [.., previous, current] => {
+                        let change = current.value as f64 - previous.value as f64;
+                        let percentage = if previous.value == 0 {
+                            0.0
+                        } else {
+                            change * 100.0 / previous.value as f64
+                        };
+                        write!(
+                            out,
+                            "<td data-value=\"{0}\">{0}</td><td data-value=\"{1}\" class=\"{2}\">{1:+.2}%</td>",
+                            current.value,
+                            percentage,
+                            class_of(current.value, previous.value)
+                        )?;
+                    }
+                    [current] => write!(
+                        out,
+                        "<td data-value=\"{0}\">{0}</td><td data-value=\"0\"></td>",
+                        current.value
+                    )?,
+                }
+            }
+            writeln!(out, "</tr>")?;
+        }
+        writeln!(out, "</tbody>\n</table>")
+    }
+}
+
+fn render_chart(out: &mut String, metric: Metric, points: &[DataPoint]) -> core::fmt::Result {
+    let min = points.iter().map(|p| p.value).min().unwrap_or(0);
+    let max = points.iter().map(|p| p.value).max().unwrap_or(0);
+    let plot_width = CHART_WIDTH - 2.0 * CHART_MARGIN;
+    let plot_height = CHART_HEIGHT - 2.0 * CHART_MARGIN;
+    let x = |index: usize| {
+        if points.len() == 1 {
+            CHART_MARGIN + plot_width / 2.0
+        } else {
+            CHART_MARGIN + plot_width * index as f64 / (points.len() - 1) as f64
+        }
+    };
+    let y = |value: u64| {
+        if max == min {
+            CHART_MARGIN + plot_height / 2.0
+        } else {
+            CHART_MARGIN + plot_height * (max - value) as f64 / (max - min) as f64
+        }
+    };
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+        CHART_WIDTH, CHART_HEIGHT
+    )?;
+    writeln!(
+        out,
+        "<text x=\"{}\" y=\"16\">{}</text>",
+        CHART_MARGIN, metric
+    )?;
+    writeln!(
+        out,
+        "<text x=\"4\" y=\"{:.1}\">{}</text>\n<text x=\"4\" y=\"{:.1}\">{}</text>",
+        y(max) - 4.0,
+        max,
+        y(min) + 12.0,
+        min
+    )?;
+    let polyline: Vec<String> = points
+        .iter()
+        .enumerate()
+        .map(|(index, point)| format!("{:.1},{:.1}", x(index), y(point.value)))
+        .collect();
+    writeln!(out, "<polyline points=\"{}\"/>", polyline.join(" "))?;
+    for (index, point) in points.iter().enumerate() {
+        let class = match index.checked_sub(1).map(|previous| &points[previous]) {
+            Some(previous) if point.value > previous.value => " class=\"regression\"",
+            _ => "",
+        };
+        let label = label(point.commit.as_deref(), point.timestamp);
+        writeln!(
+            out,
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\"{}><title>{}: {}</title></circle>",
+            x(index),
+            y(point.value),
+            class,
+            label,
+            point.value
+        )?;
+    }
+    // Commit labels of the first and the last run.
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+            x(0),
+            CHART_HEIGHT - 12.0,
+            label(first.commit.as_deref(), first.timestamp)
+        )?;
+        if points.len() > 1 {
+            writeln!(
+                out,
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+                x(points.len() - 1),
+                CHART_HEIGHT - 12.0,
+                label(last.commit.as_deref(), last.timestamp)
+            )?;
+        }
+    }
+    writeln!(out, "</svg>")
+}
+
+fn class_of(current: u64, previous: u64) -> &'static str {
+    if current > previous {
+        "regression"
+    } else if current < previous {
+        "improvement"
+    } else {
+        ""
+    }
+}
+
+/// Returns a short label of a run: abbreviated commit hash or a timestamp, if commit is unknown.
+fn label(commit: Option<&str>, timestamp: u64) -> String {
+    match commit {
+        Some(commit) => escape(&commit.chars().take(8).collect::<String>()),
+        None => timestamp.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HtmlReport;
+    use crate::history::{History, HistoryRecord};
+    use crate::parser::ParsedCallgrindOutput;
+    use crate::Metric;
+
+    #[test]
+    fn report_contains_charts_and_highlights_regressions() {
+        let path =
+            std::env::temp_dir().join(format!("calliper-html-test-{}.jsonl", std::process::id()));
+        let history = History::open(&path);
+        let records: Vec<HistoryRecord> = [(1, 100), (2, 120)]
+            .iter()
+            .map(|&(timestamp, instruction_reads)| HistoryRecord {
+                timestamp,
+                commit: Some(format!("{:040}", timestamp)),
+                bench: "bench".to_owned(),
+                scenario: "<search>".to_owned(),
+                results: ParsedCallgrindOutput::with_events(
+                    "<search>",
+                    &[(Metric::InstructionReads, instruction_reads)],
+                ),
+            })
+            .collect();
+        history.append(&records).unwrap();
+        let html = HtmlReport::new(&history).render().unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert!(html.contains("<h2>&lt;search&gt;</h2>"));
+        assert!(html.contains("<polyline"));
+        assert!(html.contains("class=\"regression\">+20.00%"));
+        assert!(!html.contains("<search>"));
+    }
+}
@@ -0,0 +1,78 @@
+//! Handling of termination signals (SIGINT/SIGTERM) received while Callgrind subprocesses are
+//! running.
+//!
+//! Callgrind subprocesses are spawned in process groups of their own, so a Ctrl-C in a terminal
+//! is delivered only to Calliper. It's up to Calliper to pass the signal on, wait for the
+//! subprocesses to exit and remove their outputs before terminating.
+
+#[cfg(unix)]
+mod imp {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use signal_hook::consts::{SIGINT, SIGTERM};
+    use signal_hook::SigId;
+
+    /// Records termination signals for as long as it's alive.
+    pub(crate) struct InterruptHandler {
+        received: Arc<AtomicUsize>,
+        ids: Vec<SigId>,
+    }
+
+    impl InterruptHandler {
+        pub(crate) fn install() -> std::io::Result<Self> {
+            let received = Arc::new(AtomicUsize::new(0));
+            let mut ids = vec![];
+            for signal in [SIGINT, SIGTERM] {
+                ids.push(signal_hook::flag::register_usize(
+                    signal,
+                    Arc::clone(&received),
+                    signal as usize,
+                )?);
+            }
+            Ok(Self { received, ids })
+        }
+        /// Returns a signal that was received since installation of the handler, if any.
+        pub(crate) fn received(&self) -> Option<i32> {
+            match self.received.load(Ordering::SeqCst) {
+                0 => None,
+                signal => Some(signal as i32),
+            }
+        }
+        /// Restores default signal disposition and terminates current process with a given signal,
+        /// as if Calliper never handled it.
+        pub(crate) fn terminate(self, signal: i32) -> ! {
+            drop(self);
+            let _ = signal_hook::low_level::emulate_default_handler(signal);
+            std::process::exit(128 + signal)
+        }
+    }
+
+    impl Drop for InterruptHandler {
+        fn drop(&mut self) {
+            for id in self.ids.drain(..) {
+                signal_hook::low_level::unregister(id);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    /// Termination signals are not handled on non-Unix platforms.
+    pub(crate) struct InterruptHandler;
+
+    impl InterruptHandler {
+        pub(crate) fn install() -> std::io::Result<Self> {
+            Ok(Self)
+        }
+        pub(crate) fn received(&self) -> Option<i32> {
+            None
+        }
+        pub(crate) fn terminate(self, signal: i32) -> ! {
+            std::process::exit(128 + signal)
+        }
+    }
+}
+
+pub(crate) use imp::InterruptHandler;
@@ -33,7 +33,7 @@ fn bench_linear_search() {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let runner = Runner::default();
+    let mut runner = Runner::default();
     let benches = [
         Scenario::new(bench_linear_search),
         Scenario::new(bench_binary_search),
@@ -46,18 +46,42 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 Calliper basically respawns self process with modified environment variable that is used by [Runner] to determine which function to run (while already running under Callgrind).
 */
 #![deny(missing_docs)]
+mod baseline;
 mod callgrind;
+mod cli;
+mod comparison;
 mod config;
 mod error;
+mod history;
+mod html;
+mod interrupt;
+mod metric;
 mod parser;
+mod policy;
+mod progress;
+mod replay;
+mod reporter;
 mod request;
+mod run_report;
 mod runner;
 mod scenario;
+mod style;
 pub mod utils;
 
+pub use baseline::{Baseline, BaselineMetadata};
+pub use comparison::{Comparison, ComparisonTable, Delta};
 pub use config::{CacheOptions, CacheParameters, ScenarioConfig};
 pub use error::CalliperError;
+pub use history::{DataPoint, History, HistoryRecord};
+pub use html::HtmlReport;
+pub use metric::{Metric, UnknownMetric};
 pub use parser::ParsedCallgrindOutput;
+pub use policy::{Regression, RegressionPolicy, Threshold};
+pub use reporter::{
+    BencherReporter, CriterionReporter, CsvReporter, JsonReporter, JunitReporter, LibtestReporter,
+    MarkdownReporter, OpenMetricsReporter, Reporter, TerminalReporter,
+};
 pub use request::ClientRequest;
+pub use run_report::{RunReport, ScenarioReport};
 pub use runner::{Report, Runner};
 pub use scenario::Scenario;
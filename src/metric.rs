@@ -0,0 +1,122 @@
+use std::str::FromStr;
+
+/// A single value collected by Callgrind (an event) or derived from events collected by
+/// Callgrind.
+#[non_exhaustive]
+#[derive(
+    Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub enum Metric {
+    /// Instructions executed (`Ir`).
+    InstructionReads,
+    /// Level 1 instruction cache read misses (`I1mr`).
+    InstructionL1Misses,
+    /// Last level instruction cache read misses (`ILmr`).
+    InstructionCacheMisses,
+    /// Memory reads (`Dr`).
+    DataReads,
+    /// Level 1 data cache read misses (`D1mr`).
+    DataL1ReadMisses,
+    /// Last level data cache read misses (`DLmr`).
+    DataCacheReadMisses,
+    /// Memory writes (`Dw`).
+    DataWrites,
+    /// Level 1 data cache write misses (`D1mw`).
+    DataL1WriteMisses,
+    /// Last level data cache write misses (`DLmw`).
+    DataCacheWriteMisses,
+    /// Estimated RAM accesses, i.e. all last level cache misses. See
+    /// [`ParsedCallgrindOutput::ram_accesses`](crate::ParsedCallgrindOutput::ram_accesses).
+    RamAccesses,
+    /// Estimated CPU cycles. See
+    /// [`ParsedCallgrindOutput::cycles`](crate::ParsedCallgrindOutput::cycles).
+    Cycles,
+}
+
+impl Metric {
+    /// All metrics, in the order in which Calliper reports them.
+    pub const ALL: [Metric; 11] = [
+        Self::InstructionReads,
+        Self::InstructionL1Misses,
+        Self::InstructionCacheMisses,
+        Self::DataReads,
+        Self::DataL1ReadMisses,
+        Self::DataCacheReadMisses,
+        Self::DataWrites,
+        Self::DataL1WriteMisses,
+        Self::DataCacheWriteMisses,
+        Self::RamAccesses,
+        Self::Cycles,
+    ];
+    /// Returns a descriptive name of a metric, e.g. `instruction_reads`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::InstructionReads => "instruction_reads",
+            Self::InstructionL1Misses => "instruction_l1_misses",
+            Self::InstructionCacheMisses => "instruction_cache_misses",
+            Self::DataReads => "data_reads",
+            Self::DataL1ReadMisses => "data_l1_read_misses",
+            Self::DataCacheReadMisses => "data_cache_read_misses",
+            Self::DataWrites => "data_writes",
+            Self::DataL1WriteMisses => "data_l1_write_misses",
+            Self::DataCacheWriteMisses => "data_cache_write_misses",
+            Self::RamAccesses => "ram_accesses",
+            Self::Cycles => "cycles",
+        }
+    }
+    /// Returns a short name of a metric. For metrics that are collected by Callgrind it's the
+    /// name of an event (e.g. `Ir`).
+    pub fn short_name(self) -> &'static str {
+        match self {
+            Self::InstructionReads => "Ir",
+            Self::InstructionL1Misses => "I1mr",
+            Self::InstructionCacheMisses => "ILmr",
+            Self::DataReads => "Dr",
+            Self::DataL1ReadMisses => "D1mr",
+            Self::DataCacheReadMisses => "DLmr",
+            Self::DataWrites => "Dw",
+            Self::DataL1WriteMisses => "D1mw",
+            Self::DataCacheWriteMisses => "DLmw",
+            Self::RamAccesses => "RAM",
+            Self::Cycles => "Cycles",
+        }
+    }
+}
+
+impl core::fmt::Display for Metric {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(fmt, "{}", self.name())
+    }
+}
+
+/// Error returned when parsing an unknown metric name.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown metric `{0}`")]
+pub struct UnknownMetric(String);
+
+impl FromStr for Metric {
+    type Err = UnknownMetric;
+
+    /// Parses both descriptive (`instruction_reads`) and short (`Ir`) metric names.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|metric| metric.name() == s || metric.short_name() == s)
+            .ok_or_else(|| UnknownMetric(s.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Metric;
+
+    #[test]
+    fn metrics_are_parsed_from_both_names() {
+        for metric in Metric::ALL {
+            assert_eq!(metric.name().parse(), Ok(metric));
+            assert_eq!(metric.short_name().parse(), Ok(metric));
+        }
+        assert!("instructions".parse::<Metric>().is_err());
+    }
+}
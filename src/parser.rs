@@ -1,8 +1,10 @@
 use core::fmt::Write;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::metric::Metric;
 
 /// Callgrind execution statistics extracted from Callgrind results file (callgrind.*.out).
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, serde::Serialize, serde::Deserialize)]
@@ -20,6 +22,26 @@ pub struct ParsedCallgrindOutput {
 }
 
 impl ParsedCallgrindOutput {
+    /// Returns name of the scenario these results belong to.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns value of a given metric, if it was collected.
+    pub fn get(&self, metric: Metric) -> Option<u64> {
+        match metric {
+            Metric::InstructionReads => self.instruction_reads,
+            Metric::InstructionL1Misses => self.instruction_l1_misses,
+            Metric::InstructionCacheMisses => self.instruction_cache_misses,
+            Metric::DataReads => self.data_reads,
+            Metric::DataL1ReadMisses => self.data_l1_read_misses,
+            Metric::DataCacheReadMisses => self.data_cache_read_misses,
+            Metric::DataWrites => self.data_writes,
+            Metric::DataL1WriteMisses => self.data_l1_write_misses,
+            Metric::DataCacheWriteMisses => self.data_cache_write_misses,
+            Metric::RamAccesses => self.ram_accesses(),
+            Metric::Cycles => self.cycles(),
+        }
+    }
     /// Estimates count of RAM hits. It does not account for presence of L2 cache, so the results
     /// are just an approximation.
     pub fn ram_accesses(&self) -> Option<u64> {
@@ -50,6 +72,44 @@ impl ParsedCallgrindOutput {
     }
 }
 
+#[cfg(test)]
+impl ParsedCallgrindOutput {
+    /// Creates results with given events, for use in tests. Events that are not given are treated
+    /// as not collected.
+    pub(crate) fn with_events(name: &str, events: &[(Metric, u64)]) -> Self {
+        let mut results = Self {
+            name: name.to_owned(),
+            instruction_reads: None,
+            instruction_l1_misses: None,
+            instruction_cache_misses: None,
+            data_reads: None,
+            data_l1_read_misses: None,
+            data_cache_read_misses: None,
+            data_writes: None,
+            data_l1_write_misses: None,
+            data_cache_write_misses: None,
+        };
+        for &(metric, value) in events {
+            let event = match metric {
+                Metric::InstructionReads => &mut results.instruction_reads,
+                Metric::InstructionL1Misses => &mut results.instruction_l1_misses,
+                Metric::InstructionCacheMisses => &mut results.instruction_cache_misses,
+                Metric::DataReads => &mut results.data_reads,
+                Metric::DataL1ReadMisses => &mut results.data_l1_read_misses,
+                Metric::DataCacheReadMisses => &mut results.data_cache_read_misses,
+                Metric::DataWrites => &mut results.data_writes,
+                Metric::DataL1WriteMisses => &mut results.data_l1_write_misses,
+                Metric::DataCacheWriteMisses => &mut results.data_cache_write_misses,
+                Metric::RamAccesses | Metric::Cycles => {
+                    panic!("`{}` is derived from other events", metric)
+                }
+            };
+            *event = Some(value);
+        }
+        results
+    }
+}
+
 impl core::fmt::Display for ParsedCallgrindOutput {
     fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let mut out = String::default();
@@ -71,7 +131,7 @@ impl core::fmt::Display for ParsedCallgrindOutput {
         print_field!(data_l1_write_misses);
         print_field!(data_cache_write_misses);
         if let Some(cycles) = self.cycles() {
-            writeln!(out, "cycles: {}", cycles)?;
+            writeln!(out, "    cycles: {}", cycles)?;
         }
 
         let out = out.trim_end();
@@ -80,17 +140,103 @@ impl core::fmt::Display for ParsedCallgrindOutput {
     }
 }
 
+/// A single file of Callgrind output. Callgrind writes a file per thread with
+/// `--separate-threads=yes` (`<path>-<thread>`) and a file per dump, e.g. requested with
+/// [`ClientRequest::DumpStats`](crate::ClientRequest::DumpStats) (`<path>.<part>`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct OutputPart {
+    pub(crate) path: PathBuf,
+    pub(crate) part: Option<u64>,
+    pub(crate) thread: Option<u64>,
+}
+
+/// Finds files Callgrind produced for given output path in addition to the file at `file`
+/// itself, sorted by part and thread number.
+pub(crate) fn find_output_parts(file: &Path) -> std::io::Result<Vec<OutputPart>> {
+    let directory = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let base = match file.file_name().and_then(|name| name.to_str()) {
+        Some(base) => base,
+        None => return Ok(vec![]),
+    };
+    let mut parts = vec![];
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        let suffix = match entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.strip_prefix(base))
+        {
+            Some(suffix) if !suffix.is_empty() => suffix.to_owned(),
+            _ => continue,
+        };
+        if let Some((part, thread)) = parse_part_suffix(&suffix) {
+            parts.push(OutputPart {
+                path: entry.path(),
+                part,
+                thread,
+            });
+        }
+    }
+    parts.sort_by_key(|part| (part.part, part.thread));
+    Ok(parts)
+}
+
+/// Parses `.<part>-<thread>` suffix of Callgrind output file name, where both components are
+/// optional.
+fn parse_part_suffix(suffix: &str) -> Option<(Option<u64>, Option<u64>)> {
+    fn number(digits: &str) -> Option<u64> {
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            digits.parse().ok()
+        } else {
+            None
+        }
+    }
+    let (rest, thread) = match suffix.rsplit_once('-') {
+        Some((rest, thread)) => (rest, Some(number(thread)?)),
+        None => (suffix, None),
+    };
+    let part = match rest {
+        "" => None,
+        rest => Some(number(rest.strip_prefix('.')?)?),
+    };
+    Some((part, thread))
+}
+
+/// Parses summary of a Callgrind output file. Fails if the file can't be read or it does not look
+/// like Callgrind output, e.g. because it lacks `events:` or `summary:` line.
 pub(crate) fn parse_callgrind_output(
     file: &Path,
     name: impl Into<String>,
-) -> ParsedCallgrindOutput {
+) -> io::Result<ParsedCallgrindOutput> {
+    let malformed = |reason: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "could not parse Callgrind output `{}`: {}",
+                file.display(),
+                reason
+            ),
+        )
+    };
     let mut events_line = None;
     let mut summary_line = None;
 
-    let file_in = File::open(file).expect("Unable to open cachegrind output file");
+    let file_in = File::open(file).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!(
+                "could not open Callgrind output `{}`: {}",
+                file.display(),
+                e
+            ),
+        )
+    })?;
 
     for line in BufReader::new(file_in).lines() {
-        let line = line.unwrap();
+        let line = line.map_err(|e| malformed(&e.to_string()))?;
         if let Some(line) = line.strip_prefix("events: ") {
             events_line = Some(line.trim().to_owned());
         }
@@ -101,15 +247,14 @@ pub(crate) fn parse_callgrind_output(
 
     match (events_line, summary_line) {
         (Some(events), Some(summary)) => {
-            let events: HashMap<_, _> = events
+            let summary = summary
                 .split_whitespace()
-                .zip(summary.split_whitespace().map(|s| {
-                    s.parse::<u64>()
-                        .expect("Unable to parse summary line from cachegrind output file")
-                }))
-                .collect();
+                .map(|s| s.parse::<u64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| malformed("summary line is not a list of numbers"))?;
+            let events: HashMap<_, _> = events.split_whitespace().zip(summary).collect();
             let name = name.into();
-            ParsedCallgrindOutput {
+            Ok(ParsedCallgrindOutput {
                 name,
                 instruction_reads: events.get("Ir").copied(),
                 instruction_l1_misses: events.get("I1mr").copied(),
@@ -120,11 +265,41 @@ pub(crate) fn parse_callgrind_output(
                 data_writes: events.get("Dw").copied(),
                 data_l1_write_misses: events.get("D1mw").copied(),
                 data_cache_write_misses: events.get("DLmw").copied(),
-            }
+            })
+        }
+        _ => Err(malformed("missing events/summary line")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_callgrind_output, parse_part_suffix};
+    use crate::Metric;
+
+    #[test]
+    fn part_and_thread_are_parsed_from_file_name() {
+        assert_eq!(parse_part_suffix(""), Some((None, None)));
+        assert_eq!(parse_part_suffix(".2"), Some((Some(2), None)));
+        assert_eq!(parse_part_suffix("-03"), Some((None, Some(3))));
+        assert_eq!(parse_part_suffix(".1-02"), Some((Some(1), Some(2))));
+        assert_eq!(parse_part_suffix("4"), None);
+        assert_eq!(parse_part_suffix(".json"), None);
+        assert_eq!(parse_part_suffix(".+1"), None);
+    }
+
+    #[test]
+    fn malformed_outputs_are_rejected() {
+        let path = std::env::temp_dir().join(format!("calliper-parser-{}", std::process::id()));
+        std::fs::write(&path, "events: Ir Dr\nsummary: 12 34\n").unwrap();
+        let results = parse_callgrind_output(&path, "search").unwrap();
+        assert_eq!(results.get(Metric::InstructionReads), Some(12));
+        assert_eq!(results.get(Metric::DataReads), Some(34));
+        for contents in ["garbage", "events: Ir\n", "events: Ir\nsummary: many\n"] {
+            std::fs::write(&path, contents).unwrap();
+            let e = parse_callgrind_output(&path, "search").unwrap_err();
+            assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
         }
-        _ => panic!(
-            "Unable to parse cachegrind output file '{}' - missing events/summary line",
-            file.display()
-        ),
+        std::fs::remove_file(&path).unwrap();
+        assert!(parse_callgrind_output(&path, "search").is_err());
     }
 }
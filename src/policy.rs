@@ -0,0 +1,187 @@
+use crate::comparison::{Comparison, Delta};
+use crate::metric::Metric;
+
+/// Maximum allowed increase of a metric.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Threshold {
+    /// Increase in percent of a baseline value, e.g. `Relative(2.5)` permits an increase of 2.5%.
+    Relative(f64),
+    /// Increase in absolute units of a metric.
+    Absolute(u64),
+}
+
+impl core::fmt::Display for Threshold {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Relative(percentage) => write!(fmt, "+{:.2}%", percentage),
+            Self::Absolute(value) => write!(fmt, "+{}", value),
+        }
+    }
+}
+
+/// Regression policy defines how much a metric is allowed to increase compared to a baseline.
+///
+/// Policies are checked only if results are compared against a baseline (see
+/// [`Runner::baseline`](crate::Runner::baseline)). A breach of any policy makes
+/// [`Runner::run`](crate::Runner::run) fail with
+/// [`CalliperError::RegressionsDetected`](crate::CalliperError::RegressionsDetected).
+/// ```
+/// use calliper::{Metric, RegressionPolicy, Threshold};
+///
+/// // Permit instruction count to increase by at most 1%, ignoring changes of less than 100 instructions.
+/// let policy = RegressionPolicy::new(Metric::InstructionReads, Threshold::Relative(1.0)).min_change(100);
+/// ```
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RegressionPolicy {
+    metric: Metric,
+    max_increase: Threshold,
+    min_change: u64,
+}
+
+impl RegressionPolicy {
+    /// Creates a policy permitting a given metric to increase by at most `max_increase`.
+    pub fn new(metric: Metric, max_increase: Threshold) -> Self {
+        Self {
+            metric,
+            max_increase,
+            min_change: 0,
+        }
+    }
+    /// Sets minimal absolute change of a metric that is considered by this policy. Smaller
+    /// changes are ignored, even if they exceed relative threshold.
+    /// Defaults to 0.
+    pub fn min_change(mut self, min_change: u64) -> Self {
+        self.min_change = min_change;
+        self
+    }
+    /// Returns a metric checked by this policy.
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+    /// Returns maximum permitted increase of a metric.
+    pub fn max_increase(&self) -> Threshold {
+        self.max_increase
+    }
+    /// Returns true if a given change of a metric violates the policy.
+    pub fn is_breached_by(&self, delta: &Delta) -> bool {
+        let difference = delta.difference();
+        if difference <= 0 || difference < i128::from(self.min_change) {
+            return false;
+        }
+        match self.max_increase {
+            Threshold::Relative(percentage) => delta
+                .percentage()
+                .map_or(true, |change| change > percentage),
+            Threshold::Absolute(value) => difference > i128::from(value),
+        }
+    }
+    /// Checks a comparison against this policy. Returns `None` if the policy holds or if checked
+    /// metric is not available.
+    pub(crate) fn check(&self, comparison: &Comparison) -> Option<Regression> {
+        let delta = comparison.delta(self.metric)?;
+        if self.is_breached_by(&delta) {
+            Some(Regression {
+                scenario: comparison.current.name().to_owned(),
+                policy: self.clone(),
+                delta,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Merges runner-wide policies with scenario policies. Scenario policies take precedence over
+/// runner-wide policies for the same metric.
+pub(crate) fn merge_policies<'a>(
+    defaults: &'a [RegressionPolicy],
+    scenario: &'a [RegressionPolicy],
+) -> impl Iterator<Item = &'a RegressionPolicy> {
+    defaults
+        .iter()
+        .filter(move |policy| !scenario.iter().any(|p| p.metric == policy.metric))
+        .chain(scenario)
+}
+
+/// A breach of a [`RegressionPolicy`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Regression {
+    /// Name of the scenario that regressed.
+    pub scenario: String,
+    /// Policy that was breached.
+    pub policy: RegressionPolicy,
+    /// Change of a metric that breached the policy.
+    pub delta: Delta,
+}
+
+impl Regression {
+    /// Describes the regression. Unless `is_colored` is false, the change is colored in terminal
+    /// output.
+    pub(crate) fn describe(&self, is_colored: bool) -> String {
+        format!(
+            "{}: {} (permitted: {})",
+            self.scenario,
+            self.delta.describe(is_colored),
+            self.policy.max_increase
+        )
+    }
+}
+
+impl core::fmt::Display for Regression {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(fmt, "{}", self.describe(false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_policies, RegressionPolicy, Threshold};
+    use crate::comparison::Delta;
+    use crate::Metric;
+
+    fn delta(baseline: u64, current: u64) -> Delta {
+        Delta {
+            metric: Metric::InstructionReads,
+            baseline,
+            current,
+        }
+    }
+
+    #[test]
+    fn relative_threshold() {
+        let policy = RegressionPolicy::new(Metric::InstructionReads, Threshold::Relative(10.0));
+        assert!(!policy.is_breached_by(&delta(100, 110)));
+        assert!(policy.is_breached_by(&delta(100, 111)));
+        assert!(!policy.is_breached_by(&delta(100, 50)));
+        assert!(policy.is_breached_by(&delta(0, 1)));
+    }
+
+    #[test]
+    fn absolute_threshold() {
+        let policy = RegressionPolicy::new(Metric::InstructionReads, Threshold::Absolute(5));
+        assert!(!policy.is_breached_by(&delta(100, 105)));
+        assert!(policy.is_breached_by(&delta(100, 106)));
+    }
+
+    #[test]
+    fn small_changes_are_ignored() {
+        let policy = RegressionPolicy::new(Metric::InstructionReads, Threshold::Relative(1.0))
+            .min_change(10);
+        assert!(!policy.is_breached_by(&delta(100, 109)));
+        assert!(policy.is_breached_by(&delta(100, 110)));
+    }
+
+    #[test]
+    fn scenario_policies_take_precedence() {
+        let defaults = [
+            RegressionPolicy::new(Metric::InstructionReads, Threshold::Absolute(1)),
+            RegressionPolicy::new(Metric::Cycles, Threshold::Absolute(1)),
+        ];
+        let scenario = [RegressionPolicy::new(
+            Metric::InstructionReads,
+            Threshold::Absolute(2),
+        )];
+        let merged: Vec<_> = merge_policies(&defaults, &scenario).collect();
+        assert_eq!(merged, [&defaults[1], &scenario[0]]);
+    }
+}
@@ -0,0 +1,253 @@
+//! Progress display of a benchmark run, shown on standard error while Callgrind processes are
+//! running.
+//!
+//! Durations of scenarios are stored in `target/calliper/<bench>/durations.json` after each run in
+//! which progress was shown, so that next runs can estimate how long they're going to take.
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::style::{self, Stream};
+use crate::utils;
+
+/// Wall time of scenarios of a bench binary, as measured during their last successful run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Durations {
+    path: PathBuf,
+    /// Durations in seconds, by scenario name.
+    durations: BTreeMap<String, f64>,
+}
+
+impl Durations {
+    /// Loads durations of a given bench binary. Missing or malformed files are treated as empty.
+    pub(crate) fn of_bench(bench: &str) -> Self {
+        let path = utils::calliper_dir()
+            .join(utils::sanitize_file_name(bench))
+            .join("durations.json");
+        let durations = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self { path, durations }
+    }
+    pub(crate) fn get(&self, scenario: &str) -> Option<Duration> {
+        self.durations
+            .get(scenario)
+            .filter(|seconds| seconds.is_finite() && **seconds >= 0.0)
+            .map(|&seconds| Duration::from_secs_f64(seconds))
+    }
+    pub(crate) fn insert(&mut self, scenario: &str, duration: Duration) {
+        self.durations
+            .insert(scenario.to_owned(), duration.as_secs_f64());
+    }
+    /// Stores durations, overwriting previously stored ones.
+    pub(crate) fn save(&self) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.durations)?)
+    }
+}
+
+/// A single status line with scenarios that are currently running, number of finished scenarios,
+/// elapsed time and an estimate of remaining time.
+pub(crate) struct Progress {
+    names: Vec<String>,
+    /// Durations of scenarios during the previous run.
+    expected: Vec<Option<Duration>>,
+    started: Vec<Option<Instant>>,
+    finished: Vec<bool>,
+    parallelism: usize,
+    start: Instant,
+    /// Currently displayed line.
+    line: String,
+}
+
+impl Progress {
+    /// Creates a progress display for given scenarios, if standard error is a terminal.
+    pub(crate) fn new(
+        names: Vec<String>,
+        durations: &Durations,
+        parallelism: usize,
+    ) -> Option<Self> {
+        if !style::is_terminal(Stream::Stderr) {
+            return None;
+        }
+        Some(Self::with_start(
+            names,
+            durations,
+            parallelism,
+            Instant::now(),
+        ))
+    }
+    fn with_start(
+        names: Vec<String>,
+        durations: &Durations,
+        parallelism: usize,
+        start: Instant,
+    ) -> Self {
+        Self {
+            expected: names.iter().map(|name| durations.get(name)).collect(),
+            started: vec![None; names.len()],
+            finished: vec![false; names.len()],
+            names,
+            parallelism,
+            start,
+            line: String::new(),
+        }
+    }
+    pub(crate) fn started(&mut self, position: usize) {
+        self.started[position] = Some(Instant::now());
+    }
+    pub(crate) fn finished(&mut self, position: usize) {
+        self.finished[position] = true;
+    }
+    /// Redraws the status line, if it has changed.
+    pub(crate) fn draw(&mut self) {
+        let mut line = self.render(Instant::now());
+        if let Some(width) = style::terminal_width(Stream::Stderr) {
+            // Wrapped lines could not be redrawn.
+            line = line.chars().take(width.saturating_sub(1)).collect();
+        }
+        if line != self.line {
+            let _ = write!(io::stderr(), "\r\x1b[2K{}", line);
+            let _ = io::stderr().flush();
+            self.line = line;
+        }
+    }
+    /// Removes the status line, so that something else can be printed in it's place. It's shown
+    /// again on next [`Self::draw`].
+    pub(crate) fn clear(&mut self) {
+        if !self.line.is_empty() {
+            let _ = write!(io::stderr(), "\r\x1b[2K");
+            let _ = io::stderr().flush();
+            self.line.clear();
+        }
+    }
+    fn render(&self, now: Instant) -> String {
+        let done = self.finished.iter().filter(|&&finished| finished).count();
+        if done == self.names.len() {
+            return String::new();
+        }
+        let running: Vec<String> = self
+            .names
+            .iter()
+            .zip(&self.started)
+            .zip(&self.finished)
+            .filter_map(|((name, started), finished)| match started {
+                Some(started) if !finished => Some(format!(
+                    "{} ({})",
+                    name,
+                    format_duration(now.saturating_duration_since(*started))
+                )),
+                _ => None,
+            })
+            .collect();
+        let mut line = format!(
+            "[{}/{}] running {} | elapsed {}",
+            done,
+            self.names.len(),
+            running.join(", "),
+            format_duration(now.saturating_duration_since(self.start))
+        );
+        if let Some(eta) = self.eta(now) {
+            line.push_str(&format!(" | ETA {}", format_duration(eta)));
+        }
+        line
+    }
+    /// Estimates remaining time from durations of scenarios during the previous run. Scenarios
+    /// that were not run before are assumed to take as long as an average one did. With
+    /// parallelism, remaining work is assumed to be spread evenly, but a run can't finish before
+    /// it's longest remaining scenario does.
+    fn eta(&self, now: Instant) -> Option<Duration> {
+        let known: Vec<f64> = self
+            .expected
+            .iter()
+            .flatten()
+            .map(Duration::as_secs_f64)
+            .collect();
+        if known.is_empty() {
+            return None;
+        }
+        let average = known.iter().sum::<f64>() / known.len() as f64;
+        let mut remaining = 0.0;
+        let mut longest: f64 = 0.0;
+        let mut unfinished = 0;
+        for ((expected, started), finished) in
+            self.expected.iter().zip(&self.started).zip(&self.finished)
+        {
+            if *finished {
+                continue;
+            }
+            let expected = expected.map_or(average, |expected| expected.as_secs_f64());
+            let elapsed = started.map_or(0.0, |started| {
+                now.saturating_duration_since(started).as_secs_f64()
+            });
+            remaining += (expected - elapsed).max(0.0);
+            longest = longest.max(expected - elapsed);
+            unfinished += 1;
+        }
+        let parallelism = self.parallelism.min(unfinished).max(1);
+        Some(Duration::from_secs_f64(
+            (remaining / parallelism as f64).max(longest),
+        ))
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3600 {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}h {:02}m", seconds / 3600, seconds % 3600 / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_duration, Durations, Progress};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn durations_are_formatted() {
+        assert_eq!(format_duration(Duration::from_millis(5900)), "5s");
+        assert_eq!(format_duration(Duration::from_secs(65)), "1m 05s");
+        assert_eq!(format_duration(Duration::from_secs(7380)), "2h 03m");
+    }
+
+    #[test]
+    fn eta_is_based_on_previous_durations() {
+        let mut durations = Durations::default();
+        durations.insert("a", Duration::from_secs(10));
+        durations.insert("b", Duration::from_secs(30));
+        let start = Instant::now();
+        let names = ["a", "b", "c"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        let mut progress = Progress::with_start(names, &durations, 1, start);
+        assert_eq!(progress.eta(start), Some(Duration::from_secs(60)));
+        progress.started[0] = Some(start);
+        let now = start + Duration::from_secs(4);
+        assert_eq!(
+            progress.render(now),
+            "[0/3] running a (4s) | elapsed 4s | ETA 56s"
+        );
+        progress.finished[0] = true;
+        progress.started[1] = Some(now);
+        assert_eq!(
+            progress.render(now + Duration::from_secs(40)),
+            "[1/3] running b (40s) | elapsed 44s | ETA 20s"
+        );
+        progress.parallelism = 2;
+        assert_eq!(progress.eta(start), Some(Duration::from_secs(30)));
+        assert_eq!(
+            Progress::with_start(vec!["a".to_owned()], &Durations::default(), 1, start).eta(start),
+            None
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Replaying of existing Callgrind outputs, e.g. to present them with different reporters, to
+//! compare them against another baseline or to look into results received from someone else.
+//!
+//! A replayed directory holds Callgrind outputs along with a `manifest.json` file, which maps
+//! names of scenarios to their outputs (relative to the directory):
+//! ```json
+//! {
+//!   "scenarios": {
+//!     "binary_search": "callgrind.out.1234",
+//!     "linear_search": "callgrind.out.1235"
+//!   }
+//! }
+//! ```
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::callgrind::{CallgrindEvent, CallgrindResultFilename};
+use crate::error::CalliperError;
+use crate::parser::parse_callgrind_output;
+use crate::scenario::Scenario;
+
+/// Name of the manifest file of a replayed directory.
+const MANIFEST: &str = "manifest.json";
+
+/// Callgrind outputs of a replayed directory.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Manifest {
+    #[serde(skip)]
+    directory: PathBuf,
+    /// Output file names, by scenario name.
+    scenarios: BTreeMap<String, PathBuf>,
+}
+
+impl Manifest {
+    /// Loads manifest of a given directory.
+    pub(crate) fn load(directory: &Path) -> Result<Self, CalliperError> {
+        let path = directory.join(MANIFEST);
+        let contents = std::fs::read_to_string(&path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("could not read `{}`: {}", path.display(), e),
+            )
+        })?;
+        let mut manifest: Self = serde_json::from_str(&contents).map_err(io::Error::from)?;
+        manifest.directory = directory.to_owned();
+        Ok(manifest)
+    }
+    /// Returns true if there's an output of a given scenario.
+    pub(crate) fn contains(&self, scenario: &str) -> bool {
+        self.scenarios.contains_key(scenario)
+    }
+    /// Passes outputs of given scenarios to `on_event`, in the same way [`spawn_callgrind`] does
+    /// with outputs of Callgrind processes it runs. Outputs are never removed.
+    ///
+    /// [`spawn_callgrind`]: crate::callgrind::spawn_callgrind
+    pub(crate) fn replay(
+        &self,
+        scenarios: &[(usize, &Scenario)],
+        mut on_event: impl FnMut(CallgrindEvent) -> Result<(), CalliperError>,
+    ) -> Result<(), CalliperError> {
+        for (position, (_, scenario)) in scenarios.iter().enumerate() {
+            on_event(CallgrindEvent::Started(position))?;
+            on_event(CallgrindEvent::Finished(
+                position,
+                Duration::ZERO,
+                self.output(&scenario.name),
+            ))?;
+        }
+        Ok(())
+    }
+    fn output(&self, scenario: &str) -> Result<CallgrindResultFilename, CalliperError> {
+        let path = match self.scenarios.get(scenario) {
+            Some(file) => self.directory.join(file),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("scenario `{}` is not listed in {}", scenario, MANIFEST),
+                )
+                .into())
+            }
+        };
+        if !path.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "Callgrind output `{}` of scenario `{}` does not exist",
+                    path.display(),
+                    scenario
+                ),
+            )
+            .into());
+        }
+        // Outputs may come from elsewhere, so they're checked before anything relies on them.
+        parse_callgrind_output(&path, scenario)?;
+        Ok(CallgrindResultFilename {
+            path: path.to_string_lossy().into_owned(),
+            should_delete: false,
+            is_generated: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Manifest;
+    use std::path::Path;
+
+    #[test]
+    fn outputs_are_relative_to_directory() {
+        let directory =
+            std::env::temp_dir().join(format!("calliper-replay-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("manifest.json"),
+            r#"{"scenarios": {"search": "callgrind.out.1", "missing": "callgrind.out.2", "malformed": "callgrind.out.3"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            directory.join("callgrind.out.1"),
+            "events: Ir\nsummary: 1000\n",
+        )
+        .unwrap();
+        std::fs::write(directory.join("callgrind.out.3"), "garbage").unwrap();
+        let manifest = Manifest::load(&directory).unwrap();
+        let output = manifest.output("search").unwrap();
+        assert_eq!(Path::new(&output.path), directory.join("callgrind.out.1"));
+        assert!(!output.should_delete);
+        assert!(manifest.contains("missing"));
+        assert!(manifest.output("missing").is_err());
+        assert!(manifest.output("other").is_err());
+        assert!(manifest.output("malformed").is_err());
+        drop(output);
+        assert!(directory.join("callgrind.out.1").exists());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
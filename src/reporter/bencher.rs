@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::error::CalliperError;
+use crate::metric::Metric;
+use crate::parser::ParsedCallgrindOutput;
+use crate::reporter::{write_output, Reporter};
+use crate::runner::Report;
+
+/// Writes results in [Bencher Metric Format](https://bencher.dev/docs/reference/bencher-metric-format/)
+/// once all scenarios are done, either to standard output or to a file.
+///
+/// Each scenario maps to following measures, as long as events they're derived from were
+/// collected:
+/// - `instructions`: instructions executed (`Ir`)
+/// - `estimated-cycles`: estimated CPU cycles (see
+///   [`ParsedCallgrindOutput::cycles`](crate::ParsedCallgrindOutput::cycles))
+/// - `l1-misses`: level 1 cache misses (`I1mr + D1mr + D1mw`)
+/// - `ll-misses`: last level cache misses (`ILmr + DLmr + DLmw`)
+///
+/// Callgrind results are deterministic, so measures have no bounds. Failed scenarios are omitted.
+///
+/// This is the default reporter when a bench binary is run with `--format bencher`, e.g.
+/// `bencher run --adapter json "cargo bench -- --format bencher"`.
+#[derive(Clone, Debug, Default)]
+pub struct BencherReporter {
+    path: Option<PathBuf>,
+}
+
+impl BencherReporter {
+    /// Creates a reporter writing to standard output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Writes results to a file at a given path instead of standard output, overwriting previous
+    /// contents of the file.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl Reporter for BencherReporter {
+    fn run_finished(
+        &mut self,
+        results: &[Result<Report<'_>, CalliperError>],
+    ) -> Result<(), CalliperError> {
+        let document: BTreeMap<String, BTreeMap<&str, Measure>> = results
+            .iter()
+            .flatten()
+            .map(|report| (report.name().to_owned(), measures(&report.parse())))
+            .collect();
+        let json = serde_json::to_string_pretty(&document).map_err(std::io::Error::from)?;
+        write_output(self.path.as_deref(), &(json + "\n"))?;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+struct Measure {
+    value: f64,
+}
+
+fn measures(results: &ParsedCallgrindOutput) -> BTreeMap<&'static str, Measure> {
+    let sum = |metrics: &[Metric]| -> Option<u64> {
+        metrics.iter().map(|&metric| results.get(metric)).sum()
+    };
+    [
+        ("instructions", results.get(Metric::InstructionReads)),
+        ("estimated-cycles", results.get(Metric::Cycles)),
+        (
+            "l1-misses",
+            sum(&[
+                Metric::InstructionL1Misses,
+                Metric::DataL1ReadMisses,
+                Metric::DataL1WriteMisses,
+            ]),
+        ),
+        (
+            "ll-misses",
+            sum(&[
+                Metric::InstructionCacheMisses,
+                Metric::DataCacheReadMisses,
+                Metric::DataCacheWriteMisses,
+            ]),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| {
+        Some((
+            name,
+            Measure {
+                value: value? as f64,
+            },
+        ))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::measures;
+    use crate::parser::ParsedCallgrindOutput;
+    use crate::Metric;
+
+    #[test]
+    fn measures_are_derived_from_events() {
+        let results = ParsedCallgrindOutput::with_events(
+            "search",
+            &[
+                (Metric::InstructionReads, 100),
+                (Metric::InstructionL1Misses, 1),
+                (Metric::InstructionCacheMisses, 1),
+                (Metric::DataReads, 20),
+                (Metric::DataL1ReadMisses, 2),
+                (Metric::DataCacheReadMisses, 1),
+                (Metric::DataWrites, 10),
+                (Metric::DataL1WriteMisses, 3),
+                (Metric::DataCacheWriteMisses, 1),
+            ],
+        );
+        assert_eq!(
+            serde_json::to_value(measures(&results)).unwrap(),
+            serde_json::json!({
+                "instructions": { "value": 100.0 },
+                "estimated-cycles": { "value": 244.0 },
+                "l1-misses": { "value": 6.0 },
+                "ll-misses": { "value": 3.0 },
+            })
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::ScenarioConfig;
+use crate::error::CalliperError;
+use crate::metric::Metric;
+use crate::reporter::Reporter;
+use crate::runner::Report;
+use crate::scenario::Scenario;
+use crate::utils;
+
+/// Environment variable Criterion reads its output directory from.
+const CRITERION_HOME: &str = "CRITERION_HOME";
+/// Characters Criterion replaces in directory names.
+const UNSAFE_CHARACTERS: [char; 9] = ['?', '"', '/', '\\', '*', '<', '>', ':', '|'];
+/// Maximum length of a directory name used by Criterion.
+const MAX_DIRECTORY_NAME_LENGTH: usize = 64;
+
+/// Writes results into the directory layout used by [Criterion](https://github.com/bheisler/criterion.rs),
+/// with a chosen metric standing in for time in nanoseconds, so that tools such as
+/// [critcmp](https://github.com/BurntSushi/critcmp) can compare Calliper benchmarks alongside
+/// Criterion ones.
+///
+/// For each scenario, `benchmark.json`, `estimates.json` and `sample.json` are written to
+/// `<criterion dir>/<group>/<scenario>/new`, and copied to a baseline directory next to it
+/// (`base` by default), just like Criterion does. Scenarios without a group are stored directly
+/// under `<criterion dir>/<scenario>`. As Callgrind results are deterministic, all estimates are
+/// equal to the measured value and have no deviation. Failed scenarios are omitted.
+///
+/// Criterion directory is taken from `CRITERION_HOME` environment variable, and defaults to
+/// `target/criterion`.
+///
+/// This reporter is registered in addition to other reporters when a bench binary is run with
+/// `--criterion` flag, using the metric given with `--metric <name>` and the baseline name given
+/// with `--save-baseline <name>`.
+#[derive(Clone, Debug)]
+pub struct CriterionReporter {
+    directory: PathBuf,
+    metric: Metric,
+    baseline: String,
+    scenarios: Vec<(String, Option<String>)>,
+}
+
+impl Default for CriterionReporter {
+    fn default() -> Self {
+        Self {
+            directory: std::env::var_os(CRITERION_HOME)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| utils::target_dir().join("criterion")),
+            metric: Metric::InstructionReads,
+            baseline: "base".to_owned(),
+            scenarios: vec![],
+        }
+    }
+}
+
+impl CriterionReporter {
+    /// Creates a new Criterion reporter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets the Criterion directory results are written to.
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = directory.into();
+        self
+    }
+    /// Sets the metric written in place of time in nanoseconds.
+    /// Defaults to [`Metric::InstructionReads`].
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+    /// Sets name of the baseline directory results are copied to. Defaults to `base`.
+    pub fn baseline(mut self, name: impl Into<String>) -> Self {
+        self.baseline = name.into();
+        self
+    }
+}
+
+impl Reporter for CriterionReporter {
+    fn run_started(
+        &mut self,
+        scenarios: &[&Scenario],
+        _defaults: &ScenarioConfig,
+    ) -> Result<(), CalliperError> {
+        self.scenarios = scenarios
+            .iter()
+            .map(|scenario| (scenario.name.clone(), scenario.group.clone()))
+            .collect();
+        Ok(())
+    }
+    fn run_finished(
+        &mut self,
+        results: &[Result<Report<'_>, CalliperError>],
+    ) -> Result<(), CalliperError> {
+        for ((name, group), result) in self.scenarios.iter().zip(results) {
+            let value = match result {
+                Ok(report) => report.parse().get(self.metric),
+                Err(_) => None,
+            };
+            if let Some(value) = value {
+                let id = BenchmarkId::new(name, group.as_deref());
+                let directory = self.directory.join(&id.directory_name);
+                for baseline in ["new".to_owned(), directory_name(&self.baseline)] {
+                    write_files(&directory.join(baseline), &id, value as f64)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_files(directory: &Path, id: &BenchmarkId, value: f64) -> Result<(), CalliperError> {
+    std::fs::create_dir_all(directory)?;
+    let write = |name: &str, document: serde_json::Value| -> Result<(), CalliperError> {
+        let json = serde_json::to_string(&document).map_err(std::io::Error::from)?;
+        std::fs::write(directory.join(name), json)?;
+        Ok(())
+    };
+    write(
+        "benchmark.json",
+        serde_json::to_value(id).map_err(std::io::Error::from)?,
+    )?;
+    write("estimates.json", estimates(value))?;
+    write(
+        "sample.json",
+        serde_json::json!({
+            "sampling_mode": "Flat",
+            "iters": [1.0],
+            "times": [value],
+        }),
+    )
+}
+
+/// Identifier of a benchmark, as stored in Criterion's `benchmark.json`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+struct BenchmarkId {
+    group_id: String,
+    function_id: Option<String>,
+    value_str: Option<String>,
+    throughput: Option<()>,
+    full_id: String,
+    directory_name: String,
+    title: String,
+}
+
+impl BenchmarkId {
+    fn new(name: &str, group: Option<&str>) -> Self {
+        let (group_id, function_id, full_id, directory_name) = match group {
+            Some(group) => (
+                group.to_owned(),
+                Some(name.to_owned()),
+                format!("{}/{}", group, name),
+                format!("{}/{}", directory_name(group), directory_name(name)),
+            ),
+            None => (name.to_owned(), None, name.to_owned(), directory_name(name)),
+        };
+        Self {
+            group_id,
+            function_id,
+            value_str: None,
+            throughput: None,
+            title: full_id.clone(),
+            full_id,
+            directory_name,
+        }
+    }
+}
+
+/// Makes a name safe to use as a directory name, the same way Criterion does.
+fn directory_name(name: &str) -> String {
+    let mut safe = name.replace(UNSAFE_CHARACTERS, "_");
+    if safe.len() > MAX_DIRECTORY_NAME_LENGTH {
+        let mut length = MAX_DIRECTORY_NAME_LENGTH;
+        while !safe.is_char_boundary(length) {
+            length -= 1;
+        }
+        safe.truncate(length);
+    }
+    safe
+}
+
+/// Returns contents of `estimates.json` for a deterministic measurement.
+fn estimates(value: f64) -> serde_json::Value {
+    let estimate = |point: f64| {
+        serde_json::json!({
+            "confidence_interval": {
+                "confidence_level": 0.95,
+                "lower_bound": point,
+                "upper_bound": point,
+            },
+            "point_estimate": point,
+            "standard_error": 0.0,
+        })
+    };
+    serde_json::json!({
+        "mean": estimate(value),
+        "median": estimate(value),
+        "median_abs_dev": estimate(0.0),
+        "slope": null,
+        "std_dev": estimate(0.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimates, BenchmarkId};
+
+    #[test]
+    fn ids_match_criterion_layout() {
+        let id = BenchmarkId::new("search: 1/2", Some("arrays"));
+        assert_eq!(id.full_id, "arrays/search: 1/2");
+        assert_eq!(id.directory_name, "arrays/search_ 1_2");
+        assert_eq!(id.function_id.as_deref(), Some("search: 1/2"));
+        let id = BenchmarkId::new(&"x".repeat(100), None);
+        assert_eq!(id.group_id.len(), 100);
+        assert_eq!(id.directory_name.len(), 64);
+        assert_eq!(id.function_id, None);
+    }
+
+    #[test]
+    fn estimates_have_no_deviation() {
+        let estimates = estimates(42.0);
+        assert_eq!(estimates["mean"]["point_estimate"], 42.0);
+        assert_eq!(
+            estimates["median"]["confidence_interval"]["upper_bound"],
+            42.0
+        );
+        assert_eq!(estimates["std_dev"]["point_estimate"], 0.0);
+        assert!(estimates["slope"].is_null());
+    }
+}
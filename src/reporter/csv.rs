@@ -0,0 +1,135 @@
+use std::path::PathBuf;
+
+use crate::error::CalliperError;
+use crate::metric::Metric;
+use crate::parser::{parse_callgrind_output, ParsedCallgrindOutput};
+use crate::reporter::{write_output, Reporter};
+use crate::runner::Report;
+
+/// Metric columns, in order. New metrics are not added automatically, so that the layout only
+/// changes deliberately; new columns may only be appended.
+const METRIC_COLUMNS: [(&str, Metric); 11] = [
+    ("instruction_reads", Metric::InstructionReads),
+    ("instruction_l1_misses", Metric::InstructionL1Misses),
+    ("instruction_cache_misses", Metric::InstructionCacheMisses),
+    ("data_reads", Metric::DataReads),
+    ("data_l1_read_misses", Metric::DataL1ReadMisses),
+    ("data_cache_read_misses", Metric::DataCacheReadMisses),
+    ("data_writes", Metric::DataWrites),
+    ("data_l1_write_misses", Metric::DataL1WriteMisses),
+    ("data_cache_write_misses", Metric::DataCacheWriteMisses),
+    ("ram_accesses", Metric::RamAccesses),
+    ("cycles", Metric::Cycles),
+];
+
+/// Writes results as CSV once all scenarios are done, either to standard output or to a file.
+///
+/// Each scenario has a row with the same results as [`Report::parse`], in which `part` and
+/// `thread` columns are empty. If Callgrind split it's output into multiple files (one per thread
+/// with `--separate-threads=yes` or one per [dump](crate::ClientRequest::DumpStats)), each
+/// additional file has a row of it's own with `part` and/or `thread` set. Such files are only
+/// looked for next to outputs named by Calliper, not next to ones set with
+/// [`ScenarioConfig::output`](crate::ScenarioConfig::output) or replayed. Failed scenarios are
+/// omitted.
+///
+/// Columns are `scenario`, `group`, `part`, `thread`, followed by `instruction_reads`,
+/// `instruction_l1_misses`, `instruction_cache_misses`, `data_reads`, `data_l1_read_misses`,
+/// `data_cache_read_misses`, `data_writes`, `data_l1_write_misses`, `data_cache_write_misses`,
+/// `ram_accesses` and `cycles`. Columns are only ever appended to this list, so that existing
+/// consumers keep working. Metrics that were not collected are left empty.
+///
+/// This is the default reporter when a bench binary is run with `--format csv`.
+#[derive(Clone, Debug, Default)]
+pub struct CsvReporter {
+    path: Option<PathBuf>,
+}
+
+impl CsvReporter {
+    /// Creates a reporter writing to standard output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Writes results to a file at a given path instead of standard output, overwriting previous
+    /// contents of the file.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl Reporter for CsvReporter {
+    fn run_finished(
+        &mut self,
+        results: &[Result<Report<'_>, CalliperError>],
+    ) -> Result<(), CalliperError> {
+        let mut out = header();
+        for report in results.iter().flatten() {
+            write_row(&mut out, report, None, None, &report.parse());
+            for part in report.output_parts()? {
+                let results = parse_callgrind_output(&part.path, report.name())?;
+                write_row(&mut out, report, part.part, part.thread, &results);
+            }
+        }
+        write_output(self.path.as_deref(), &out)?;
+        Ok(())
+    }
+}
+
+fn header() -> String {
+    let mut columns = vec!["scenario", "group", "part", "thread"];
+    columns.extend(METRIC_COLUMNS.iter().map(|(name, _)| *name));
+    columns.join(",") + "\n"
+}
+
+fn write_row(
+    out: &mut String,
+    report: &Report<'_>,
+    part: Option<u64>,
+    thread: Option<u64>,
+    results: &ParsedCallgrindOutput,
+) {
+    let optional = |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+    let mut fields = vec![
+        escape(report.name()),
+        escape(report.group().unwrap_or_default()),
+        optional(part),
+        optional(thread),
+    ];
+    fields.extend(
+        METRIC_COLUMNS
+            .iter()
+            .map(|&(_, metric)| optional(results.get(metric))),
+    );
+    out.push_str(&fields.join(","));
+    out.push('\n');
+}
+
+/// Quotes a field if it contains characters that are special in CSV (RFC 4180).
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, header};
+
+    #[test]
+    fn columns_are_stable() {
+        assert_eq!(
+            header(),
+            "scenario,group,part,thread,instruction_reads,instruction_l1_misses,\
+             instruction_cache_misses,data_reads,data_l1_read_misses,data_cache_read_misses,\
+             data_writes,data_l1_write_misses,data_cache_write_misses,ram_accesses,cycles\n"
+        );
+    }
+
+    #[test]
+    fn special_characters_are_quoted() {
+        assert_eq!(escape("search"), "search");
+        assert_eq!(escape("search, \"fast\""), "\"search, \"\"fast\"\"\"");
+    }
+}
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use crate::config::ScenarioConfig;
+use crate::error::CalliperError;
+use crate::reporter::{write_output, Reporter};
+use crate::run_report::{RunReport, ScenarioReport};
+use crate::runner::Report;
+use crate::scenario::Scenario;
+
+/// Writes a [`RunReport`] as a JSON document once all scenarios are done, either to standard
+/// output or to a file.
+///
+/// This is the default reporter when a bench binary is run with `--format json`.
+#[derive(Clone, Debug, Default)]
+pub struct JsonReporter {
+    path: Option<PathBuf>,
+    scenarios: Vec<ScenarioReport>,
+}
+
+impl JsonReporter {
+    /// Creates a reporter writing to standard output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Writes the report to a file at a given path instead of standard output, overwriting
+    /// previous contents of the file.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl Reporter for JsonReporter {
+    fn run_started(
+        &mut self,
+        scenarios: &[&Scenario],
+        defaults: &ScenarioConfig,
+    ) -> Result<(), CalliperError> {
+        self.scenarios = scenarios
+            .iter()
+            .map(|scenario| ScenarioReport::pending(scenario, defaults))
+            .collect();
+        Ok(())
+    }
+    fn run_finished(
+        &mut self,
+        results: &[Result<Report<'_>, CalliperError>],
+    ) -> Result<(), CalliperError> {
+        for (scenario, result) in self.scenarios.iter_mut().zip(results) {
+            scenario.finish(result);
+        }
+        let report = RunReport::from_scenarios(std::mem::take(&mut self.scenarios));
+        let json = serde_json::to_string_pretty(&report).map_err(std::io::Error::from)?;
+        write_output(self.path.as_deref(), &(json + "\n"))?;
+        Ok(())
+    }
+}
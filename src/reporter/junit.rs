@@ -0,0 +1,173 @@
+use core::fmt::Write as _;
+use std::path::PathBuf;
+
+use crate::config::ScenarioConfig;
+use crate::error::CalliperError;
+use crate::metric::Metric;
+use crate::reporter::{write_output, Reporter};
+use crate::runner::Report;
+use crate::scenario::Scenario;
+use crate::utils::{self, escape};
+
+/// Writes results as a JUnit XML document once all scenarios are done, either to standard output
+/// or to a file, so that they can be shown by CI systems alongside unit tests.
+///
+/// Each scenario is a `<testcase>` with collected metrics as it's properties. Breaches of
+/// [regression policies](crate::RegressionPolicy) and failed scenarios (e.g. due to a
+/// [timeout](crate::ScenarioConfig::timeout)) are reported as `<failure>` elements, with changes of
+/// all metrics relative to a baseline in their body.
+///
+/// This is the default reporter when a bench binary is run with `--format junit`.
+#[derive(Clone, Debug, Default)]
+pub struct JunitReporter {
+    path: Option<PathBuf>,
+    scenarios: Vec<(String, Option<String>)>,
+}
+
+impl JunitReporter {
+    /// Creates a reporter writing to standard output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Writes the document to a file at a given path instead of standard output, overwriting
+    /// previous contents of the file.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl Reporter for JunitReporter {
+    fn run_started(
+        &mut self,
+        scenarios: &[&Scenario],
+        _defaults: &ScenarioConfig,
+    ) -> Result<(), CalliperError> {
+        self.scenarios = scenarios
+            .iter()
+            .map(|scenario| (scenario.name.clone(), scenario.group.clone()))
+            .collect();
+        Ok(())
+    }
+    fn run_finished(
+        &mut self,
+        results: &[Result<Report<'_>, CalliperError>],
+    ) -> Result<(), CalliperError> {
+        let out = utils::render_string(|out| {
+            render(out, &utils::bench_name(), &self.scenarios, results)
+        })?;
+        write_output(self.path.as_deref(), &out)?;
+        Ok(())
+    }
+}
+
+fn render(
+    out: &mut String,
+    bench: &str,
+    scenarios: &[(String, Option<String>)],
+    results: &[Result<Report<'_>, CalliperError>],
+) -> core::fmt::Result {
+    let failures = results
+        .iter()
+        .filter(|result| match result {
+            Ok(report) => !report.regressions().is_empty(),
+            Err(_) => true,
+        })
+        .count();
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(out, "<testsuites>")?;
+    writeln!(
+        out,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\">",
+        escape(bench),
+        results.len(),
+        failures
+    )?;
+    for ((name, group), result) in scenarios.iter().zip(results) {
+        let classname = match group {
+            Some(group) => format!("{}.{}", bench, group),
+            None => bench.to_owned(),
+        };
+        writeln!(
+            out,
+            "    <testcase name=\"{}\" classname=\"{}\">",
+            escape(name),
+            escape(&classname)
+        )?;
+        match result {
+            Ok(report) => render_report(out, report)?,
+            Err(e) => writeln!(
+                out,
+                "      <failure type=\"error\" message=\"{}\"/>",
+                escape(&e.to_string())
+            )?,
+        }
+        writeln!(out, "    </testcase>")?;
+    }
+    writeln!(out, "  </testsuite>")?;
+    writeln!(out, "</testsuites>")
+}
+
+fn render_report(out: &mut String, report: &Report<'_>) -> core::fmt::Result {
+    let results = report.parse();
+    writeln!(out, "      <properties>")?;
+    for metric in Metric::ALL {
+        if let Some(value) = results.get(metric) {
+            writeln!(
+                out,
+                "        <property name=\"{}\" value=\"{}\"/>",
+                metric, value
+            )?;
+        }
+    }
+    writeln!(out, "      </properties>")?;
+    let regressions = report.regressions();
+    if regressions.is_empty() {
+        return Ok(());
+    }
+    let message: Vec<String> = regressions
+        .iter()
+        .map(|regression| regression.describe(false))
+        .collect();
+    let details: Vec<String> = report
+        .comparison()
+        .map(|comparison| {
+            comparison
+                .deltas()
+                .iter()
+                .map(|delta| delta.describe(false))
+                .collect()
+        })
+        .unwrap_or_default();
+    writeln!(
+        out,
+        "      <failure type=\"regression\" message=\"{}\">{}</failure>",
+        escape(&message.join("; ")),
+        escape(&details.join("\n"))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use crate::error::CalliperError;
+    use std::time::Duration;
+
+    #[test]
+    fn failed_scenarios_are_failures() {
+        let mut out = String::new();
+        render(
+            &mut out,
+            "bench",
+            &[("a <b>".to_owned(), Some("group".to_owned()))],
+            &[Err(CalliperError::Timeout {
+                name: "a <b>".to_owned(),
+                timeout: Duration::from_secs(1),
+            })],
+        )
+        .unwrap();
+        assert!(out.contains("tests=\"1\" failures=\"1\""));
+        assert!(out.contains("<testcase name=\"a &lt;b&gt;\" classname=\"bench.group\">"));
+        assert!(out.contains("<failure type=\"error\" message=\""));
+    }
+}
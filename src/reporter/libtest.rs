@@ -0,0 +1,120 @@
+use crate::config::ScenarioConfig;
+use crate::error::CalliperError;
+use crate::metric::Metric;
+use crate::reporter::Reporter;
+use crate::runner::Report;
+use crate::scenario::Scenario;
+use crate::style::group_digits;
+
+/// Prints results in the same shape as libtest benchmarks do, with a chosen metric in place of
+/// nanoseconds, e.g.:
+/// ```text
+/// test bench_binary_search ... bench:         179 ns/iter (+/- 0)
+/// ```
+/// so that tools parsing libtest output (such as `cargo-benchcmp`) work with Calliper benchmarks.
+/// As Callgrind results are deterministic, deviation is always 0.
+///
+/// This is the default reporter when a bench binary is run with `--format libtest`.
+#[derive(Clone, Debug)]
+pub struct LibtestReporter {
+    metric: Metric,
+    measured: usize,
+    ignored: usize,
+    failed: usize,
+}
+
+impl Default for LibtestReporter {
+    fn default() -> Self {
+        Self {
+            metric: Metric::InstructionReads,
+            measured: 0,
+            ignored: 0,
+            failed: 0,
+        }
+    }
+}
+
+impl LibtestReporter {
+    /// Creates a new libtest-like reporter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets the metric printed in place of `ns/iter`. It can also be set with `--metric <name>`
+    /// command line option.
+    /// Defaults to [`Metric::InstructionReads`].
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+}
+
+impl Reporter for LibtestReporter {
+    fn run_started(
+        &mut self,
+        scenarios: &[&Scenario],
+        _defaults: &ScenarioConfig,
+    ) -> Result<(), CalliperError> {
+        self.measured = 0;
+        self.ignored = 0;
+        self.failed = 0;
+        println!("\nrunning {} tests", scenarios.len());
+        Ok(())
+    }
+    fn scenario_finished(&mut self, report: &Report<'_>) -> Result<(), CalliperError> {
+        match report.parse().get(self.metric) {
+            Some(value) => {
+                self.measured += 1;
+                println!("{}", bench_line(report.name(), value));
+            }
+            // Metric was not collected.
+            None => {
+                self.ignored += 1;
+                println!("test {} ... ignored", report.name());
+            }
+        }
+        Ok(())
+    }
+    fn scenario_failed(
+        &mut self,
+        scenario: &Scenario,
+        _error: &CalliperError,
+    ) -> Result<(), CalliperError> {
+        self.failed += 1;
+        println!("test {} ... FAILED", scenario.name);
+        Ok(())
+    }
+    fn run_finished(
+        &mut self,
+        _results: &[Result<Report<'_>, CalliperError>],
+    ) -> Result<(), CalliperError> {
+        println!(
+            "\ntest result: {}. 0 passed; {} failed; {} ignored; {} measured; 0 filtered out\n",
+            if self.failed == 0 { "ok" } else { "FAILED" },
+            self.failed,
+            self.ignored,
+            self.measured
+        );
+        Ok(())
+    }
+}
+
+fn bench_line(name: &str, value: u64) -> String {
+    format!(
+        "test {} ... bench: {:>11} ns/iter (+/- 0)",
+        name,
+        group_digits(value)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bench_line;
+
+    #[test]
+    fn lines_match_libtest_format() {
+        assert_eq!(
+            bench_line("search", 1234567),
+            "test search ... bench:   1,234,567 ns/iter (+/- 0)"
+        );
+    }
+}
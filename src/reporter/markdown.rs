@@ -0,0 +1,332 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::config::ScenarioConfig;
+use crate::error::CalliperError;
+use crate::metric::Metric;
+use crate::policy::Threshold;
+use crate::reporter::Reporter;
+use crate::runner::Report;
+use crate::scenario::Scenario;
+use crate::utils;
+
+/// Environment variable with a path of GitHub Actions job summary.
+const GITHUB_STEP_SUMMARY: &str = "GITHUB_STEP_SUMMARY";
+/// Environment variable set to `true` when running under GitHub Actions.
+const GITHUB_ACTIONS: &str = "GITHUB_ACTIONS";
+
+/// Writes a Markdown table with baseline value, current value and change of a metric for each
+/// scenario, suitable for pull request comments and CI job summaries.
+///
+/// The table is appended to a configured file, or to the file named by `GITHUB_STEP_SUMMARY`
+/// environment variable, so that it shows up in a summary of a GitHub Actions job. If neither is
+/// set, it's printed to standard output.
+///
+/// Additionally, [workflow command](https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions)
+/// annotations are printed to standard output: `::error` for breaches of
+/// [regression policies](crate::RegressionPolicy) and failed scenarios, and `::warning` for other
+/// increases of the metric above [a threshold](Self::warning_threshold). Annotations are enabled by
+/// default under GitHub Actions only.
+///
+/// This is the default reporter when a bench binary is run with `--format markdown`.
+#[derive(Clone, Debug)]
+pub struct MarkdownReporter {
+    path: Option<PathBuf>,
+    metric: Metric,
+    annotations: bool,
+    warning_threshold: Threshold,
+    scenarios: Vec<String>,
+}
+
+impl Default for MarkdownReporter {
+    fn default() -> Self {
+        Self {
+            path: None,
+            metric: Metric::InstructionReads,
+            annotations: utils::get_env_flag(GITHUB_ACTIONS).unwrap_or(false),
+            warning_threshold: Threshold::Relative(1.0),
+            scenarios: vec![],
+        }
+    }
+}
+
+impl MarkdownReporter {
+    /// Creates a new Markdown reporter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Appends the table to a file at a given path, instead of the file named by
+    /// `GITHUB_STEP_SUMMARY`.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+    /// Sets the metric shown in the table and checked for warnings. It can also be set with
+    /// `--metric <name>` command line option.
+    /// Defaults to [`Metric::InstructionReads`].
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+    /// Enables or disables workflow command annotations.
+    /// Defaults to true if `GITHUB_ACTIONS` environment variable is set to `true`.
+    pub fn annotations(mut self, is_enabled: bool) -> Self {
+        self.annotations = is_enabled;
+        self
+    }
+    /// Sets how much the metric has to increase for a `::warning` annotation, so that noise does
+    /// not end up in pull requests. Increases that breach a regression policy are annotated as
+    /// errors regardless.
+    /// Defaults to [`Threshold::Relative(1.0)`](Threshold::Relative), i.e. 1%.
+    pub fn warning_threshold(mut self, threshold: Threshold) -> Self {
+        self.warning_threshold = threshold;
+        self
+    }
+}
+
+impl Reporter for MarkdownReporter {
+    fn run_started(
+        &mut self,
+        scenarios: &[&Scenario],
+        _defaults: &ScenarioConfig,
+    ) -> Result<(), CalliperError> {
+        self.scenarios = scenarios
+            .iter()
+            .map(|scenario| scenario.name.clone())
+            .collect();
+        Ok(())
+    }
+    fn run_finished(
+        &mut self,
+        results: &[Result<Report<'_>, CalliperError>],
+    ) -> Result<(), CalliperError> {
+        let rows: Vec<Row> = self
+            .scenarios
+            .iter()
+            .zip(results)
+            .map(|(name, result)| Row::new(name, result, self.metric))
+            .collect();
+        let table = render(&utils::bench_name(), self.metric, &rows);
+        let path = self
+            .path
+            .clone()
+            .or_else(|| std::env::var_os(GITHUB_STEP_SUMMARY).map(PathBuf::from));
+        match path {
+            Some(path) => OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(table.as_bytes())?,
+            None => print!("{}", table),
+        }
+        if self.annotations {
+            for row in &rows {
+                if let Some(annotation) = row.annotation(self.metric, self.warning_threshold) {
+                    println!("{}", annotation);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of a single scenario, as shown in the table.
+struct Row {
+    name: String,
+    baseline: Option<u64>,
+    current: Option<u64>,
+    regressions: Vec<String>,
+    error: Option<String>,
+}
+
+impl Row {
+    fn new(name: &str, result: &Result<Report<'_>, CalliperError>, metric: Metric) -> Self {
+        match result {
+            Ok(report) => Self {
+                name: name.to_owned(),
+                baseline: report
+                    .baseline()
+                    .and_then(|baseline| baseline.results.get(metric)),
+                current: report.parse().get(metric),
+                regressions: report
+                    .regressions()
+                    .iter()
+                    .map(|regression| regression.describe(false))
+                    .collect(),
+                error: None,
+            },
+            Err(e) => Self {
+                name: name.to_owned(),
+                baseline: None,
+                current: None,
+                regressions: vec![],
+                error: Some(e.to_string()),
+            },
+        }
+    }
+    fn change(&self) -> Option<f64> {
+        match (self.baseline, self.current) {
+            (Some(baseline), Some(current)) if baseline > 0 => {
+                Some((current as f64 - baseline as f64) * 100.0 / baseline as f64)
+            }
+            _ => None,
+        }
+    }
+    fn is_above(&self, threshold: Threshold) -> bool {
+        match (self.baseline, self.current, threshold) {
+            (Some(baseline), Some(current), Threshold::Absolute(value)) => {
+                current.saturating_sub(baseline) > value
+            }
+            (_, _, Threshold::Relative(percentage)) => {
+                self.change().map_or(false, |change| change > percentage)
+            }
+            _ => false,
+        }
+    }
+    fn annotation(&self, metric: Metric, threshold: Threshold) -> Option<String> {
+        let (command, message) = if let Some(error) = &self.error {
+            ("error", format!("Scenario failed: {}", error))
+        } else if !self.regressions.is_empty() {
+            (
+                "error",
+                format!("Performance regression: {}", self.regressions.join("; ")),
+            )
+        } else {
+            let change = self
+                .change()
+                .filter(|&change| change > 0.0 && self.is_above(threshold))?;
+            (
+                "warning",
+                format!("{} of {} increased by {:.2}%", metric, self.name, change),
+            )
+        };
+        Some(format!(
+            "::{} title={}::{}",
+            command,
+            escape_property(&format!("Benchmark {}", self.name)),
+            escape_data(&message)
+        ))
+    }
+}
+
+fn render(bench: &str, metric: Metric, rows: &[Row]) -> String {
+    let optional = |value: Option<u64>| value.map_or_else(|| "-".to_owned(), |v| v.to_string());
+    let mut out = format!(
+        "### Benchmark results: {}\n\n| Scenario | Baseline {} | Current {} | Change |\n| :--- | ---: | ---: | ---: |\n",
+        escape_cell(bench),
+        metric.short_name(),
+        metric.short_name()
+    );
+    for row in rows {
+        let change = match (&row.error, row.change()) {
+            (Some(error), _) => format!("failed: {}", escape_cell(error)),
+            (None, Some(change)) if !row.regressions.is_empty() => {
+                format!("**{:+.2}%** :x:", change)
+            }
+            (None, Some(change)) => format!("{:+.2}%", change),
+            // Policy of another metric was breached.
+            (None, None) if !row.regressions.is_empty() => "- :x:".to_owned(),
+            (None, None) => "-".to_owned(),
+        };
+        out.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            escape_cell(&row.name),
+            optional(row.baseline),
+            optional(row.current),
+            change
+        ));
+    }
+    out.push('\n');
+    out
+}
+
+/// Escapes characters that would break a Markdown table cell.
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Escapes a message of a workflow command.
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a property value of a workflow command.
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, Row};
+    use crate::{Metric, Threshold};
+
+    fn row(name: &str, baseline: Option<u64>, current: Option<u64>) -> Row {
+        Row {
+            name: name.to_owned(),
+            baseline,
+            current,
+            regressions: vec![],
+            error: None,
+        }
+    }
+
+    #[test]
+    fn table_shows_changes() {
+        let mut regressed = row("slow|search", Some(100), Some(150));
+        regressed.regressions.push("slow".to_owned());
+        let rows = [
+            row("search", Some(200), Some(150)),
+            regressed,
+            row("new", None, Some(1)),
+        ];
+        assert_eq!(
+            render("bench", Metric::InstructionReads, &rows),
+            "### Benchmark results: bench
+
+| Scenario | Baseline Ir | Current Ir | Change |
+| :--- | ---: | ---: | ---: |
+| search | 200 | 150 | -25.00% |
+| slow\\|search | 100 | 150 | **+50.00%** :x: |
+| new | - | 1 | - |
+
+"
+        );
+    }
+
+    #[test]
+    fn increases_are_annotated() {
+        let threshold = Threshold::Relative(1.0);
+        assert_eq!(
+            row("search, fast", Some(100), Some(110)).annotation(Metric::InstructionReads, threshold),
+            Some(
+                "::warning title=Benchmark search%2C fast::instruction_reads of search, fast increased by 10.00%25"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            row("search", Some(100), Some(90)).annotation(Metric::InstructionReads, threshold),
+            None
+        );
+        assert_eq!(
+            row("search", Some(1000), Some(1005)).annotation(Metric::InstructionReads, threshold),
+            None
+        );
+        assert!(row("search", Some(1000), Some(1005))
+            .annotation(Metric::InstructionReads, Threshold::Absolute(4))
+            .is_some());
+        assert!(row("search", Some(1000), Some(1005))
+            .annotation(Metric::InstructionReads, Threshold::Absolute(5))
+            .is_none());
+        let mut regressed = row("search", Some(100), Some(110));
+        regressed.regressions.push("search: too slow".to_owned());
+        assert!(regressed
+            .annotation(Metric::InstructionReads, Threshold::Relative(50.0))
+            .unwrap()
+            .starts_with(
+                "::error title=Benchmark search::Performance regression: search: too slow"
+            ));
+    }
+}
@@ -0,0 +1,101 @@
+//! Reporters present results of a benchmark run, e.g. by printing them to a terminal or by
+//! writing them to a file.
+mod bencher;
+mod criterion;
+mod csv;
+mod json;
+mod junit;
+mod libtest;
+mod markdown;
+mod openmetrics;
+mod terminal;
+
+pub use bencher::BencherReporter;
+pub use criterion::CriterionReporter;
+pub use csv::CsvReporter;
+pub use json::JsonReporter;
+pub use junit::JunitReporter;
+pub use libtest::LibtestReporter;
+pub use markdown::MarkdownReporter;
+pub use openmetrics::OpenMetricsReporter;
+pub use terminal::TerminalReporter;
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::config::ScenarioConfig;
+use crate::error::CalliperError;
+use crate::runner::Report;
+use crate::scenario::Scenario;
+
+/// Receives notifications about progress of a benchmark run.
+///
+/// Reporters are registered with [`Runner::reporter`](crate::Runner::reporter). All methods have
+/// default no-op implementations, so a reporter only needs to implement the ones it's interested
+/// in.
+/// ```no_run
+/// use calliper::{CalliperError, Report, Reporter, Runner};
+///
+/// struct InstructionCounts;
+///
+/// impl Reporter for InstructionCounts {
+///     fn scenario_finished(&mut self, report: &Report<'_>) -> Result<(), CalliperError> {
+///         let results = report.parse();
+///         println!("{}: {:?}", report.name(), results.get(calliper::Metric::InstructionReads));
+///         Ok(())
+///     }
+/// }
+///
+/// let runner = Runner::default().reporter(InstructionCounts);
+/// ```
+pub trait Reporter {
+    /// Called once before any scenario is run, with all scenarios that are going to be run and
+    /// the default configuration of the runner (see [`Runner::config`](crate::Runner::config)).
+    fn run_started(
+        &mut self,
+        _scenarios: &[&Scenario],
+        _defaults: &ScenarioConfig,
+    ) -> Result<(), CalliperError> {
+        Ok(())
+    }
+    /// Called when a scenario has finished successfully. With
+    /// [parallelism](crate::Runner::parallelism), scenarios may finish in a different order than
+    /// they were passed to [`Self::run_started`].
+    fn scenario_finished(&mut self, _report: &Report<'_>) -> Result<(), CalliperError> {
+        Ok(())
+    }
+    /// Called when a scenario has failed, e.g. due to a [timeout](crate::ScenarioConfig::timeout).
+    fn scenario_failed(
+        &mut self,
+        _scenario: &Scenario,
+        _error: &CalliperError,
+    ) -> Result<(), CalliperError> {
+        Ok(())
+    }
+    /// Called once all scenarios have finished, with results of all of them. Results are in the
+    /// same order as scenarios passed to [`Self::run_started`].
+    fn run_finished(
+        &mut self,
+        _results: &[Result<Report<'_>, CalliperError>],
+    ) -> Result<(), CalliperError> {
+        Ok(())
+    }
+}
+
+/// Writes output of a reporter to a file at a given path, or to standard output if there's no
+/// path. Missing directories are created and the file is replaced atomically, so that its readers
+/// never see partial output.
+pub(crate) fn write_output(path: Option<&Path>, contents: &str) -> io::Result<()> {
+    match path {
+        Some(path) => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut temporary = path.as_os_str().to_owned();
+            temporary.push(".tmp");
+            std::fs::write(&temporary, contents)?;
+            std::fs::rename(&temporary, path)
+        }
+        None => io::stdout().write_all(contents.as_bytes()),
+    }
+}
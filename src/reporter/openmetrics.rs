@@ -0,0 +1,147 @@
+use core::fmt::Write as _;
+use std::path::PathBuf;
+
+use crate::error::CalliperError;
+use crate::metric::Metric;
+use crate::reporter::{write_output, Reporter};
+use crate::runner::Report;
+use crate::utils;
+
+/// Writes results in [OpenMetrics](https://openmetrics.io/) text format once all scenarios are
+/// done, either to standard output or to a file, e.g. for node_exporter's textfile collector.
+///
+/// Each metric is a gauge named `calliper_<metric name>` (e.g. `calliper_instruction_reads`),
+/// with `scenario`, `bench`, `commit` and `cache` labels. `commit` is `unknown` if current Git
+/// commit could not be determined; `cache` holds simulated cache parameters (see
+/// [`CacheOptions`](crate::CacheOptions)), or `disabled` if cache simulation was off. Failed
+/// scenarios are omitted.
+///
+/// Files are replaced atomically, so that a collector never sees partially written results.
+///
+/// This is the default reporter when a bench binary is run with `--format openmetrics`.
+#[derive(Clone, Debug, Default)]
+pub struct OpenMetricsReporter {
+    path: Option<PathBuf>,
+}
+
+impl OpenMetricsReporter {
+    /// Creates a reporter writing to standard output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Writes results to a file at a given path instead of standard output, replacing previous
+    /// contents of the file.
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+}
+
+impl Reporter for OpenMetricsReporter {
+    fn run_finished(
+        &mut self,
+        results: &[Result<Report<'_>, CalliperError>],
+    ) -> Result<(), CalliperError> {
+        let commit = utils::git_commit().unwrap_or_else(|| "unknown".to_owned());
+        let samples: Vec<Sample> = results
+            .iter()
+            .flatten()
+            .map(|report| {
+                let results = report.parse();
+                Sample {
+                    labels: [
+                        ("scenario", report.name().to_owned()),
+                        ("bench", utils::bench_name()),
+                        ("commit", commit.clone()),
+                        (
+                            "cache",
+                            report
+                                .config()
+                                .cache
+                                .as_ref()
+                                .map_or_else(|| "disabled".to_owned(), |cache| cache.to_string()),
+                        ),
+                    ],
+                    values: Metric::ALL.map(|metric| results.get(metric)),
+                }
+            })
+            .collect();
+        let out = utils::render_string(|out| render(out, &samples))?;
+        write_output(self.path.as_deref(), &out)?;
+        Ok(())
+    }
+}
+
+/// Values of all metrics of a single scenario, in the order of [`Metric::ALL`].
+struct Sample {
+    labels: [(&'static str, String); 4],
+    values: [Option<u64>; Metric::ALL.len()],
+}
+
+fn render(out: &mut String, samples: &[Sample]) -> core::fmt::Result {
+    for (index, metric) in Metric::ALL.iter().enumerate() {
+        if samples.iter().all(|sample| sample.values[index].is_none()) {
+            continue;
+        }
+        writeln!(out, "# TYPE calliper_{} gauge", metric)?;
+        writeln!(
+            out,
+            "# HELP calliper_{} Callgrind metric {}.",
+            metric,
+            metric.short_name()
+        )?;
+        for sample in samples {
+            if let Some(value) = sample.values[index] {
+                let labels: Vec<String> = sample
+                    .labels
+                    .iter()
+                    .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+                    .collect();
+                writeln!(out, "calliper_{}{{{}}} {}", metric, labels.join(","), value)?;
+            }
+        }
+    }
+    writeln!(out, "# EOF")
+}
+
+/// Escapes a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, Sample};
+    use crate::Metric;
+
+    #[test]
+    fn samples_are_grouped_by_metric() {
+        let sample = |scenario: &str, instruction_reads| {
+            let mut values = [None; Metric::ALL.len()];
+            values[0] = Some(instruction_reads);
+            Sample {
+                labels: [
+                    ("scenario", scenario.to_owned()),
+                    ("bench", "bench".to_owned()),
+                    ("commit", "unknown".to_owned()),
+                    ("cache", "disabled".to_owned()),
+                ],
+                values,
+            }
+        };
+        let mut out = String::new();
+        render(&mut out, &[sample("a", 1), sample("\"b\"", 2)]).unwrap();
+        assert_eq!(
+            out,
+            r#"# TYPE calliper_instruction_reads gauge
+# HELP calliper_instruction_reads Callgrind metric Ir.
+calliper_instruction_reads{scenario="a",bench="bench",commit="unknown",cache="disabled"} 1
+calliper_instruction_reads{scenario="\"b\"",bench="bench",commit="unknown",cache="disabled"} 2
+# EOF
+"#
+        );
+    }
+}
@@ -0,0 +1,411 @@
+use crate::comparison::{signed, Comparison, ComparisonTable, Delta};
+use crate::error::CalliperError;
+use crate::metric::Metric;
+use crate::parser::ParsedCallgrindOutput;
+use crate::reporter::Reporter;
+use crate::runner::Report;
+use crate::scenario::Scenario;
+use crate::style::{self, group_digits, paint, Color, Stream};
+
+/// Metrics in the order in which they're shown in a results table. If the terminal is too narrow
+/// to fit all of them, columns are dropped from the end.
+const COLUMNS: [Metric; 11] = [
+    Metric::InstructionReads,
+    Metric::Cycles,
+    Metric::RamAccesses,
+    Metric::DataReads,
+    Metric::DataWrites,
+    Metric::InstructionL1Misses,
+    Metric::DataL1ReadMisses,
+    Metric::DataL1WriteMisses,
+    Metric::InstructionCacheMisses,
+    Metric::DataCacheReadMisses,
+    Metric::DataCacheWriteMisses,
+];
+
+/// Prints results to standard output as a table with one row per scenario and one column per
+/// metric, followed by a [comparison table](ComparisonTable) if any scenario is a reference.
+///
+/// Values compared against a [baseline](crate::Runner::baseline) are shown along with the previous
+/// value and the absolute and relative change, e.g. `1,000 → 1,100 (+100, +10.00%)`. Output is
+/// colored and fitted to the width of the terminal only if it's written to a terminal; colors can
+/// also be disabled with `NO_COLOR` environment variable.
+///
+/// This is the reporter used by [`Runner`](crate::Runner) if no other reporter is registered.
+#[derive(Clone, Debug, Default)]
+pub struct TerminalReporter {
+    verbose: bool,
+}
+
+impl TerminalReporter {
+    /// Creates a new terminal reporter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// If set to true, a breakdown of all events is printed for each scenario as soon as it
+    /// finishes, instead of a summary table.
+    ///
+    /// The default reporter can be made verbose with `--verbose` command line option.
+    /// Defaults to false.
+    pub fn verbose(mut self, is_enabled: bool) -> Self {
+        self.verbose = is_enabled;
+        self
+    }
+}
+
+impl Reporter for TerminalReporter {
+    fn scenario_finished(&mut self, report: &Report<'_>) -> Result<(), CalliperError> {
+        if self.verbose {
+            println!(
+                "{}",
+                breakdown(&report.parse(), report.comparison().as_ref())
+            );
+        }
+        Ok(())
+    }
+    fn scenario_failed(
+        &mut self,
+        scenario: &Scenario,
+        error: &CalliperError,
+    ) -> Result<(), CalliperError> {
+        println!(
+            "{}\n    {}",
+            scenario.name,
+            paint(format!("error: {}", error), Color::Red)
+        );
+        Ok(())
+    }
+    fn run_finished(
+        &mut self,
+        results: &[Result<Report<'_>, CalliperError>],
+    ) -> Result<(), CalliperError> {
+        if !self.verbose {
+            let rows: Vec<Row> = results
+                .iter()
+                .flatten()
+                .map(|report| Row {
+                    results: report.parse(),
+                    comparison: report.comparison(),
+                })
+                .collect();
+            if !rows.is_empty() {
+                print!(
+                    "{}",
+                    render_table(&rows, style::terminal_width(Stream::Stdout))
+                );
+            }
+        }
+        for regression in results
+            .iter()
+            .flatten()
+            .flat_map(|report| report.regressions())
+        {
+            println!(
+                "{} {}",
+                paint("regression:", Color::Red),
+                regression.describe(true)
+            );
+        }
+        let table = ComparisonTable::new(results.iter().flatten());
+        if !table.is_empty() {
+            println!("\n{}", table.to_string().trim_end());
+        }
+        Ok(())
+    }
+}
+
+struct Row {
+    results: ParsedCallgrindOutput,
+    comparison: Option<Comparison>,
+}
+
+/// Separates a previous value of a metric from it's current value.
+const ARROW: &str = " → ";
+
+/// A table cell consisting of a value, optionally preceded by a previous value and followed by a
+/// colored change of that value.
+struct Cell {
+    previous: Option<String>,
+    value: String,
+    change: Option<(String, Option<Color>)>,
+}
+
+impl Cell {
+    fn new(value: impl Into<String>) -> Self {
+        Self {
+            previous: None,
+            value: value.into(),
+            change: None,
+        }
+    }
+    /// Creates a cell of a metric value, compared to a previous value if there's one.
+    fn compared(value: u64, delta: Option<Delta>) -> Self {
+        match delta {
+            Some(delta) => Self {
+                previous: Some(group_digits(delta.baseline)),
+                value: group_digits(value),
+                change: Some(change(&delta)),
+            },
+            None => Self::new(group_digits(value)),
+        }
+    }
+    fn previous_width(&self) -> usize {
+        self.previous.as_ref().map_or(0, |previous| {
+            previous.chars().count() + ARROW.chars().count()
+        })
+    }
+    fn value_width(&self) -> usize {
+        self.value.chars().count()
+    }
+    fn change_width(&self) -> usize {
+        self.change
+            .as_ref()
+            .map_or(0, |(change, _)| change.chars().count() + 1)
+    }
+    /// Renders a cell with it's previous value (if any) right-aligned to `previous_width`, it's
+    /// value right-aligned to `value_width` and a change left-aligned to `change_width`. Padding
+    /// is computed before painting, as escape codes do not take any space.
+    fn render(&self, previous_width: usize, value_width: usize, change_width: usize) -> String {
+        let mut text = match &self.previous {
+            Some(previous) => format!(
+                "{:>width$}{}",
+                previous,
+                ARROW,
+                width = previous_width.saturating_sub(ARROW.chars().count())
+            ),
+            None => " ".repeat(previous_width),
+        };
+        text.push_str(&format!("{:>width$}", self.value, width = value_width));
+        if let Some((change, color)) = &self.change {
+            text.push(' ');
+            match color {
+                Some(color) => text.push_str(&paint(change, *color)),
+                None => text.push_str(change),
+            }
+        }
+        text.push_str(&" ".repeat(change_width.saturating_sub(self.change_width())));
+        text
+    }
+}
+
+/// Formats a change of a metric as e.g. `(+1,234, +1.25%)`, colored red for regressions and
+/// green for improvements.
+fn change(delta: &Delta) -> (String, Option<Color>) {
+    let text = match delta.percentage() {
+        Some(percentage) => format!("({}, {:+.2}%)", signed(delta.difference()), percentage),
+        None => format!("({})", signed(delta.difference())),
+    };
+    let color = if delta.is_regression() {
+        Some(Color::Red)
+    } else if delta.is_improvement() {
+        Some(Color::Green)
+    } else {
+        None
+    };
+    (text, color)
+}
+
+/// Renders results as a table, fitting it within `max_width` columns (if any) by omitting
+/// trailing metrics. Results compared against a baseline are shown as
+/// `previous → current (+change, +percentage%)`.
+fn render_table(rows: &[Row], max_width: Option<usize>) -> String {
+    let metrics: Vec<Metric> = COLUMNS
+        .iter()
+        .copied()
+        .filter(|&metric| rows.iter().any(|row| row.results.get(metric).is_some()))
+        .collect();
+    let columns: Vec<Vec<Cell>> = metrics
+        .iter()
+        .map(|&metric| {
+            rows.iter()
+                .map(|row| match row.results.get(metric) {
+                    Some(value) => Cell::compared(
+                        value,
+                        row.comparison
+                            .as_ref()
+                            .and_then(|comparison| comparison.delta(metric)),
+                    ),
+                    None => Cell::new("-"),
+                })
+                .collect()
+        })
+        .collect();
+    // Width of previous values, of values (including header) and of changes of each column.
+    let widths: Vec<(usize, usize, usize)> = metrics
+        .iter()
+        .zip(&columns)
+        .map(|(metric, column)| {
+            let previous_width = column.iter().map(Cell::previous_width).max().unwrap_or(0);
+            let value_width = column.iter().map(Cell::value_width).max().unwrap_or(0);
+            let change_width = column.iter().map(Cell::change_width).max().unwrap_or(0);
+            let header_width = metric.short_name().len();
+            (
+                previous_width,
+                value_width.max(header_width.saturating_sub(previous_width + change_width)),
+                change_width,
+            )
+        })
+        .collect();
+    let name_width = std::iter::once("scenario")
+        .chain(rows.iter().map(|row| row.results.name()))
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut shown = 0;
+    let mut total_width = 4 + name_width;
+    for (previous_width, value_width, change_width) in &widths {
+        total_width += 2 + previous_width + value_width + change_width;
+        if shown > 0 && max_width.map_or(false, |max_width| total_width > max_width) {
+            break;
+        }
+        shown += 1;
+    }
+    let mut out = format!("    {:<width$}", "scenario", width = name_width);
+    for (metric, (previous_width, value_width, change_width)) in
+        metrics.iter().zip(&widths).take(shown)
+    {
+        out.push_str(&format!(
+            "  {:>value_width$}{:change_width$}",
+            metric.short_name(),
+            "",
+            value_width = previous_width + value_width,
+            change_width = change_width
+        ));
+    }
+    out = format!("{}\n", out.trim_end());
+    for (index, row) in rows.iter().enumerate() {
+        let mut line = format!("    {:<width$}", row.results.name(), width = name_width);
+        for (column, (previous_width, value_width, change_width)) in
+            columns.iter().zip(&widths).take(shown)
+        {
+            line.push_str("  ");
+            line.push_str(&column[index].render(*previous_width, *value_width, *change_width));
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    if shown < metrics.len() {
+        out.push_str(&format!(
+            "    ({} more metric(s) do not fit in the terminal, use --verbose to see all of them)\n",
+            metrics.len() - shown
+        ));
+    }
+    out
+}
+
+/// Renders all collected metrics of a single scenario, one per line.
+fn breakdown(results: &ParsedCallgrindOutput, comparison: Option<&Comparison>) -> String {
+    let lines: Vec<(String, Cell)> = Metric::ALL
+        .iter()
+        .filter_map(|&metric| {
+            let value = results.get(metric)?;
+            let label = format!("{} ({})", metric.name(), metric.short_name());
+            let delta = comparison.and_then(|comparison| comparison.delta(metric));
+            Some((label, Cell::compared(value, delta)))
+        })
+        .collect();
+    let label_width = lines
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+    let previous_width = lines
+        .iter()
+        .map(|(_, cell)| cell.previous_width())
+        .max()
+        .unwrap_or(0);
+    let value_width = lines
+        .iter()
+        .map(|(_, cell)| cell.value_width())
+        .max()
+        .unwrap_or(0);
+    let mut out = results.name().to_owned();
+    for (label, cell) in &lines {
+        let line = format!(
+            "    {:<width$}  {}",
+            label,
+            cell.render(previous_width, value_width, 0),
+            width = label_width
+        );
+        out.push('\n');
+        out.push_str(line.trim_end());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{breakdown, render_table, Row};
+    use crate::comparison::Comparison;
+    use crate::parser::ParsedCallgrindOutput;
+    use crate::Metric;
+
+    fn results(name: &str, instruction_reads: u64, data_reads: u64) -> ParsedCallgrindOutput {
+        ParsedCallgrindOutput::with_events(
+            name,
+            &[
+                (Metric::InstructionReads, instruction_reads),
+                (Metric::DataReads, data_reads),
+            ],
+        )
+    }
+
+    #[test]
+    fn table_is_aligned() {
+        let rows = [
+            Row {
+                results: results("linear_search", 1234567, 20),
+                comparison: Some(Comparison {
+                    baseline: results("linear_search", 1000000, 20),
+                    current: results("linear_search", 1234567, 20),
+                }),
+            },
+            Row {
+                results: results("binary", 15, 1000),
+                comparison: None,
+            },
+        ];
+        assert_eq!(
+            render_table(&rows, None),
+            "    scenario                          Ir                              Dr
+    linear_search  1,000,000 → 1,234,567 (+234,567, +23.46%)  20 →    20 (+0, +0.00%)
+    binary                            15                           1,000
+"
+        );
+    }
+
+    #[test]
+    fn columns_that_do_not_fit_are_omitted() {
+        let rows = [Row {
+            results: results("search", 100, 20),
+            comparison: None,
+        }];
+        let table = render_table(&rows, Some(20));
+        assert!(table.starts_with("    scenario   Ir\n    search    100\n"));
+        assert!(table.contains("1 more metric(s)"));
+    }
+
+    #[test]
+    fn breakdown_lists_all_metrics() {
+        assert_eq!(
+            breakdown(&results("search", 12345, 20), None),
+            "search
+    instruction_reads (Ir)  12,345
+    data_reads (Dr)             20"
+        );
+    }
+
+    #[test]
+    fn breakdown_shows_previous_values() {
+        let comparison = Comparison {
+            baseline: results("search", 20000, 0),
+            current: results("search", 12345, 20),
+        };
+        assert_eq!(
+            breakdown(&comparison.current, Some(&comparison)),
+            "search
+    instruction_reads (Ir)  20,000 → 12,345 (-7,655, -38.27%)
+    data_reads (Dr)              0 →     20 (+20)"
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Owned, serializable results of a whole benchmark run.
+use std::path::PathBuf;
+
+use crate::config::ScenarioConfig;
+use crate::error::CalliperError;
+use crate::parser::ParsedCallgrindOutput;
+use crate::policy::Regression;
+use crate::runner::Report;
+use crate::scenario::Scenario;
+use crate::utils;
+
+/// Results of all scenarios of a single run, detached from [`Scenario`]s they were produced by.
+///
+/// Run reports serialize to JSON documents of the following shape (see `--format json`):
+/// ```json
+/// {
+///   "schema_version": 1,
+///   "bench": "array_searching",
+///   "timestamp": 1700000000,
+///   "commit": "0123456789abcdef0123456789abcdef01234567",
+///   "calliper_version": "0.1.4",
+///   "scenarios": [
+///     {
+///       "name": "bench_linear_search",
+///       "group": null,
+///       "is_reference": true,
+///       "config": { "branch_sim": true, "...": "..." },
+///       "output_path": null,
+///       "results": { "name": "bench_linear_search", "instruction_reads": 1234, "...": "..." },
+///       "baseline": null,
+///       "regressions": [],
+///       "error": null
+///     }
+///   ]
+/// }
+/// ```
+/// `results` hold raw Callgrind events (derived metrics such as cycles are not stored) and are
+/// `null` for scenarios that failed, in which case `error` describes the failure.
+///
+/// [`RunReport::SCHEMA_VERSION`] is incremented whenever the format changes in a way that's not
+/// backwards compatible; adding new fields is considered compatible.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RunReport {
+    /// Version of the schema of serialized report.
+    pub schema_version: u32,
+    /// Name of the bench binary.
+    pub bench: String,
+    /// Time of the run, in seconds since Unix epoch.
+    pub timestamp: u64,
+    /// Git commit that was checked out during the run, if it could be determined.
+    pub commit: Option<String>,
+    /// Version of Calliper that produced the report.
+    pub calliper_version: String,
+    /// Results of each scenario, in the order in which scenarios were passed to the runner.
+    pub scenarios: Vec<ScenarioReport>,
+}
+
+/// Results of a single scenario within a [`RunReport`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ScenarioReport {
+    /// Name of the scenario.
+    pub name: String,
+    /// Group of the scenario (see [`Scenario::group`]).
+    pub group: Option<String>,
+    /// True if the scenario is a reference of it's group (see [`Scenario::reference`]).
+    pub is_reference: bool,
+    /// Configuration the scenario was run with.
+    pub config: ScenarioConfig,
+    /// Path of Callgrind output file, if it's kept after the run (see
+    /// [`ScenarioConfig::cleanup_files`]).
+    pub output_path: Option<PathBuf>,
+    /// Parsed Callgrind results, unless the scenario failed.
+    pub results: Option<ParsedCallgrindOutput>,
+    /// Results of a baseline the scenario was compared against, if there was one.
+    pub baseline: Option<ParsedCallgrindOutput>,
+    /// Breaches of regression policies of the scenario.
+    pub regressions: Vec<Regression>,
+    /// Description of a failure, if the scenario failed.
+    pub error: Option<String>,
+}
+
+impl RunReport {
+    /// Version of JSON schema of run reports produced by this version of Calliper.
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    /// Creates a report out of results returned by [`Runner::run`](crate::Runner::run).
+    /// `results` have to be in the same order as `scenarios` they were produced by. `defaults` is
+    /// the configuration passed to [`Runner::config`](crate::Runner::config); it's used to report
+    /// configuration of failed scenarios.
+    pub fn new<'a>(
+        scenarios: impl IntoIterator<Item = &'a Scenario>,
+        results: &[Result<Report<'_>, CalliperError>],
+        defaults: &ScenarioConfig,
+    ) -> Self {
+        let scenarios = scenarios
+            .into_iter()
+            .zip(results)
+            .map(|(scenario, result)| ScenarioReport::new(scenario, result, defaults))
+            .collect();
+        Self::from_scenarios(scenarios)
+    }
+
+    pub(crate) fn from_scenarios(scenarios: Vec<ScenarioReport>) -> Self {
+        Self {
+            schema_version: Self::SCHEMA_VERSION,
+            bench: utils::bench_name(),
+            timestamp: utils::unix_timestamp(),
+            commit: utils::git_commit(),
+            calliper_version: env!("CARGO_PKG_VERSION").to_owned(),
+            scenarios,
+        }
+    }
+}
+
+impl ScenarioReport {
+    /// Creates a report of a scenario that did not finish yet, with runner's `defaults` applied to
+    /// it's configuration.
+    pub(crate) fn pending(scenario: &Scenario, defaults: &ScenarioConfig) -> Self {
+        Self {
+            name: scenario.name.clone(),
+            group: scenario.group.clone(),
+            is_reference: scenario.is_reference,
+            config: defaults.clone().overwrite(scenario.config.clone()),
+            output_path: None,
+            results: None,
+            baseline: None,
+            regressions: vec![],
+            error: None,
+        }
+    }
+
+    pub(crate) fn new(
+        scenario: &Scenario,
+        result: &Result<Report<'_>, CalliperError>,
+        defaults: &ScenarioConfig,
+    ) -> Self {
+        let mut ret = Self::pending(scenario, defaults);
+        ret.finish(result);
+        ret
+    }
+
+    /// Fills in results of a scenario once it's done.
+    pub(crate) fn finish(&mut self, result: &Result<Report<'_>, CalliperError>) {
+        match result {
+            Ok(report) => {
+                self.config = report.config().clone();
+                self.output_path = report.output_path().map(PathBuf::from);
+                self.results = Some(report.parse());
+                self.baseline = report.baseline().map(|baseline| baseline.results.clone());
+                self.regressions = report.regressions().to_vec();
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RunReport, ScenarioReport};
+    use crate::config::ScenarioConfig;
+    use crate::Scenario;
+
+    #[test]
+    fn report_round_trips_through_json() {
+        let report = RunReport {
+            schema_version: RunReport::SCHEMA_VERSION,
+            bench: "bench".to_owned(),
+            timestamp: 1,
+            commit: None,
+            calliper_version: "0.0.0".to_owned(),
+            scenarios: vec![ScenarioReport {
+                name: "search".to_owned(),
+                group: Some("searching".to_owned()),
+                is_reference: false,
+                config: ScenarioConfig::default().branch_sim(true),
+                output_path: None,
+                results: None,
+                baseline: None,
+                regressions: vec![],
+                error: Some("Scenario `search` timed out".to_owned()),
+            }],
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["scenarios"][0]["name"], "search");
+        assert_eq!(serde_json::from_value::<RunReport>(json).unwrap(), report);
+    }
+
+    #[test]
+    fn pending_reports_have_defaults_applied() {
+        let scenario = Scenario::new_with_command(std::process::Command::new("true"))
+            .name("search")
+            .config(ScenarioConfig::default().filters(["search"]));
+        let defaults = ScenarioConfig::default().branch_sim(true);
+        let report = ScenarioReport::pending(&scenario, &defaults);
+        assert!(report.config.get_branch_sim());
+        assert_eq!(report.config.get_filters(), ["search"]);
+    }
+}
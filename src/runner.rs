@@ -1,16 +1,38 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::callgrind::{spawn_callgrind, CallgrindResultFilename};
+use crate::baseline::Baseline;
+use crate::callgrind::{
+    is_valgrind_available, reproduction_command, spawn_callgrind, CallgrindEvent,
+    CallgrindResultFilename,
+};
+use crate::cli::{Arguments, OutputFormat};
+use crate::comparison::Comparison;
 use crate::config::ScenarioConfig;
 use crate::error::CalliperError;
-use crate::parser::{parse_callgrind_output, ParsedCallgrindOutput};
+use crate::history::{History, HistoryRecord};
+use crate::html::HtmlReport;
+use crate::interrupt::InterruptHandler;
+use crate::metric::Metric;
+use crate::parser::{find_output_parts, parse_callgrind_output, OutputPart, ParsedCallgrindOutput};
+use crate::policy::{merge_policies, Regression, RegressionPolicy};
+use crate::progress::{Durations, Progress};
+use crate::replay::Manifest;
+use crate::reporter::{
+    BencherReporter, CriterionReporter, CsvReporter, JsonReporter, JunitReporter, LibtestReporter,
+    MarkdownReporter, OpenMetricsReporter, Reporter, TerminalReporter,
+};
 use crate::{utils, Scenario};
 /// Results for a given [`Scenario`](struct.Scenario.html).
 #[derive(Clone, Debug)]
 pub struct Report<'a> {
     run: &'a Scenario,
     run_idx: usize,
+    config: ScenarioConfig,
     results: CallgrindResultFilename,
+    parsed: ParsedCallgrindOutput,
+    baseline: Option<Baseline>,
+    regressions: Vec<Regression>,
+    reference: Option<ParsedCallgrindOutput>,
 }
 
 impl<'a> PartialEq for Report<'a> {
@@ -20,13 +42,95 @@ impl<'a> PartialEq for Report<'a> {
 }
 
 impl Report<'_> {
+    /// Returns name of the scenario.
+    pub fn name(&self) -> &str {
+        &self.run.name
+    }
+    /// Returns group of the scenario (see [`Scenario::group`]).
+    pub fn group(&self) -> Option<&str> {
+        self.run.group.as_deref()
+    }
+    /// Returns true if the scenario is a reference of it's group (see [`Scenario::reference`]).
+    pub fn is_reference(&self) -> bool {
+        self.run.is_reference
+    }
+    /// Compares results with results of a reference scenario of the group. Returns `None` for a
+    /// reference scenario itself and for groups without a reference.
+    pub fn relative_comparison(&self) -> Option<Comparison> {
+        self.reference.as_ref().map(|reference| Comparison {
+            baseline: reference.clone(),
+            current: self.parse(),
+        })
+    }
     /// Get raw contents of Callgrind output file.
     pub fn raw(&self) -> std::io::Result<String> {
         std::fs::read_to_string(&self.results.path)
     }
-    /// Get parsed contents of Callgrind output file.
+    /// Returns path of Callgrind output file, if it's kept after the run (see
+    /// [`ScenarioConfig::cleanup_files`]). Otherwise the file is removed once the report is dropped.
+    pub fn output_path(&self) -> Option<&str> {
+        if self.results.should_delete {
+            None
+        } else {
+            Some(&self.results.path)
+        }
+    }
+    /// Returns additional files Callgrind split the output into (see [`find_output_parts`]). Files
+    /// next to a user-chosen or replayed output are not necessarily Callgrind's, so they're only
+    /// looked for if Calliper named the output.
+    pub(crate) fn output_parts(&self) -> std::io::Result<Vec<OutputPart>> {
+        if self.results.is_generated {
+            find_output_parts(Path::new(&self.results.path))
+        } else {
+            Ok(vec![])
+        }
+    }
+    /// Get parsed contents of Callgrind output file. The file is parsed once the scenario has
+    /// finished; a malformed output is reported as an error of the scenario instead.
     pub fn parse(&self) -> ParsedCallgrindOutput {
-        parse_callgrind_output(Path::new(&self.results.path), &self.run.name)
+        self.parsed.clone()
+    }
+    /// Returns a shell command that runs the scenario under Callgrind the same way Calliper did,
+    /// including environment variables the scenario is looked up by. It's useful for rerunning a
+    /// single scenario by hand, e.g. with extra Valgrind options or to inspect it's output with
+    /// KCachegrind.
+    ///
+    /// Bench binary run with `--dry-run` prints these commands instead of running scenarios.
+    pub fn reproduction_command(&self) -> String {
+        reproduction_command(self.run, &self.config, self.run_idx)
+    }
+    /// Configuration the scenario was run with, with runner defaults applied.
+    pub fn config(&self) -> &ScenarioConfig {
+        &self.config
+    }
+    /// Baseline the results are compared against (see [`Runner::baseline`]), if there's one.
+    pub fn baseline(&self) -> Option<&Baseline> {
+        self.baseline.as_ref()
+    }
+    /// Returns breaches of [regression policies](RegressionPolicy) of the scenario.
+    pub fn regressions(&self) -> &[Regression] {
+        &self.regressions
+    }
+    /// Compares results with a baseline, if there's one.
+    pub fn comparison(&self) -> Option<Comparison> {
+        self.baseline.as_ref().map(|baseline| Comparison {
+            baseline: baseline.results.clone(),
+            current: self.parse(),
+        })
+    }
+}
+
+/// Prints parsed results, along with changes relative to a baseline (if there's one).
+impl core::fmt::Display for Report<'_> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.comparison() {
+            Some(comparison) => write!(fmt, "{}", comparison)?,
+            None => write!(fmt, "{}", self.parse())?,
+        }
+        for regression in &self.regressions {
+            write!(fmt, "\n    regression: {}", regression)?;
+        }
+        Ok(())
     }
 }
 
@@ -35,20 +139,51 @@ impl Report<'_> {
 /// Runner's responsibile for:
 /// - managing shared and scenario-specific configuration
 /// - spawning Callgrind subprocesses
-/// - reporting results
+/// - reporting results (see [`Reporter`])
 ///
 /// Runner also executes benchmarks in separate Callgrind processes.
-#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Runner {
-    _parallelism: usize,
+    parallelism: usize,
     defaults: ScenarioConfig,
+    require_valgrind: bool,
+    save_baseline: Option<String>,
+    baseline: Option<String>,
+    policies: Vec<RegressionPolicy>,
+    record_history: bool,
+    html_report: Option<PathBuf>,
+    replay: Option<PathBuf>,
+    reporters: Vec<Box<dyn Reporter + Send + Sync>>,
+}
+
+impl core::fmt::Debug for Runner {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt.debug_struct("Runner")
+            .field("parallelism", &self.parallelism)
+            .field("defaults", &self.defaults)
+            .field("require_valgrind", &self.require_valgrind)
+            .field("save_baseline", &self.save_baseline)
+            .field("baseline", &self.baseline)
+            .field("policies", &self.policies)
+            .field("record_history", &self.record_history)
+            .field("html_report", &self.html_report)
+            .field("replay", &self.replay)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Default for Runner {
     fn default() -> Self {
         Self {
-            _parallelism: 1,
+            parallelism: 1,
             defaults: ScenarioConfig::default(),
+            require_valgrind: false,
+            save_baseline: None,
+            baseline: None,
+            policies: vec![],
+            record_history: false,
+            html_report: None,
+            replay: None,
+            reporters: vec![],
         }
     }
 }
@@ -68,26 +203,230 @@ impl Runner {
         self.defaults = config;
         self
     }
-    #[doc(hidden)]
-    /// An upper bound of Callgrind instances running at the same time. Since Callgrind does not measure wall time, it is acceptable to
-    /// run different scenarios in parallel.
+    /// An upper bound of Callgrind instances running at the same time. Since Callgrind does not
+    /// measure wall time, it is acceptable to run different scenarios in parallel.
+    ///
+    /// Scenarios may finish out of order, so results are passed to reporters and to the callback
+    /// of [`Self::run_with`] in order of completion. Results returned by [`Self::run`] are always
+    /// in the order of scenarios.
     /// Defaults to 1.
     pub fn parallelism(mut self, parallelism: usize) -> Self {
         assert_ne!(parallelism, 0);
-        self._parallelism = parallelism;
+        self.parallelism = parallelism;
+        self
+    }
+    /// If set to true, a missing Valgrind executable is reported as
+    /// [`CalliperError::ValgrindNotFound`]. Otherwise Calliper prints a warning and skips all
+    /// scenarios, which lets benchmarks "pass" on machines without Valgrind.
+    ///
+    /// This setting can be overridden with `CALLIPER_REQUIRE_VALGRIND` environment variable (e.g.
+    /// `CALLIPER_REQUIRE_VALGRIND=1` in CI).
+    /// Defaults to false.
+    pub fn require_valgrind(mut self, is_required: bool) -> Self {
+        self.require_valgrind = is_required;
         self
     }
 
+    /// Saves results of each scenario as a baseline with a given name, overwriting previous
+    /// baseline with the same name. Baselines are stored in
+    /// `target/calliper/<bench>/<scenario>/<name>.json`.
+    ///
+    /// It can also be set with `--save-baseline <name>` command line option, which takes precedence.
+    pub fn save_baseline(mut self, name: impl Into<String>) -> Self {
+        self.save_baseline = Some(name.into());
+        self
+    }
+
+    /// Compares results of each scenario with a baseline of a given name, previously saved with
+    /// [`Self::save_baseline`]. Scenarios without such baseline are not compared.
+    ///
+    /// It can also be set with `--baseline <name>` command line option, which takes precedence.
+    pub fn baseline(mut self, name: impl Into<String>) -> Self {
+        self.baseline = Some(name.into());
+        self
+    }
+
+    /// Add a regression policy for all scenarios. Scenarios can override it with a policy of
+    /// their own for the same metric (see [`Scenario::policy`]).
+    ///
+    /// If any policy is breached, [`Self::run`] prints a summary of breaches to standard error and
+    /// fails with [`CalliperError::RegressionsDetected`] once all scenarios are done.
+    pub fn policy(mut self, policy: RegressionPolicy) -> Self {
+        self.policies.push(policy);
+        self
+    }
+
+    /// If set to true, results of each run are appended to run history of the bench binary,
+    /// stored in `target/calliper/<bench>/history.jsonl`. See [`History`] for querying it.
+    ///
+    /// It can also be enabled with `--record-history` command line option.
+    /// Defaults to false.
+    pub fn record_history(mut self, is_enabled: bool) -> Self {
+        self.record_history = is_enabled;
+        self
+    }
+
+    /// Writes an [HTML report](HtmlReport) of run history to a given path after each run.
+    ///
+    /// It can also be set with `--html-report <path>` command line option, which takes precedence.
+    pub fn html_report(mut self, path: impl Into<PathBuf>) -> Self {
+        self.html_report = Some(path.into());
+        self
+    }
+
+    /// Builds reports from existing Callgrind outputs in a given directory instead of running
+    /// scenarios under Callgrind. Outputs are mapped to scenarios by `manifest.json` file in the
+    /// directory:
+    /// ```json
+    /// { "scenarios": { "binary_search": "callgrind.out.1234" } }
+    /// ```
+    /// Scenarios that are not listed in the manifest are skipped. Replayed results are reported,
+    /// compared and saved as baselines just like fresh ones, but they're not recorded in run
+    /// history. Valgrind is not needed.
+    ///
+    /// It can also be set with `--replay <dir>` command line option, which takes precedence.
+    pub fn replay(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.replay = Some(directory.into());
+        self
+    }
+
+    /// Registers a reporter that's notified about progress of each run. Multiple reporters can be
+    /// registered; they're notified in order of registration.
+    ///
+    /// If no reporter is registered, results are printed with a [`TerminalReporter`], or with a
+    /// reporter matching `--format <json|csv|junit|markdown|bencher|openmetrics|libtest>` option
+    /// of the bench binary ([`JsonReporter`], [`CsvReporter`], [`JunitReporter`],
+    /// [`MarkdownReporter`], [`BencherReporter`], [`OpenMetricsReporter`] and [`LibtestReporter`]
+    /// respectively). Reporters showing a single metric use the one given with `--metric <name>`.
+    /// With `--criterion` flag, a [`CriterionReporter`] is registered as well.
+    pub fn reporter(mut self, reporter: impl Reporter + Send + Sync + 'static) -> Self {
+        self.reporters.push(Box::new(reporter));
+        self
+    }
+
+    fn is_valgrind_required(&self) -> bool {
+        utils::get_env_flag(utils::CALLIPER_REQUIRE_VALGRIND).unwrap_or(self.require_valgrind)
+    }
+
+    /// Returns Valgrind path of the first scenario for which Valgrind is not available.
+    fn find_missing_valgrind(&self, settings: &[&Scenario]) -> Option<String> {
+        let mut checked: Vec<String> = vec![];
+        for run in settings {
+            let config = self.defaults.clone().overwrite(run.config.clone());
+            let valgrind = config.get_valgrind();
+            if checked.iter().any(|path| path == valgrind) {
+                continue;
+            }
+            if !is_valgrind_available(valgrind) {
+                return Some(valgrind.to_owned());
+            }
+            checked.push(valgrind.to_owned());
+        }
+        None
+    }
+
+    /// Runs each function scenario once without Callgrind, checking that it does not panic.
+    /// Command scenarios are ignored.
+    fn run_natively(runs: &[&Scenario]) -> Result<(), CalliperError> {
+        let mut failed = vec![];
+        let mut ignored = 0;
+        println!("\nrunning {} tests", runs.len());
+        for run in runs {
+            let status = match run.func {
+                Some(func) => {
+                    if std::panic::catch_unwind(func).is_ok() {
+                        "ok"
+                    } else {
+                        failed.push(run.name.clone());
+                        "FAILED"
+                    }
+                }
+                None => {
+                    ignored += 1;
+                    "ignored"
+                }
+            };
+            println!("test {} ... {}", run.name, status);
+        }
+        println!(
+            "\ntest result: {}. {} passed; {} failed; {} ignored\n",
+            if failed.is_empty() { "ok" } else { "FAILED" },
+            runs.len() - failed.len() - ignored,
+            failed.len(),
+            ignored
+        );
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(CalliperError::ScenariosFailed { names: failed })
+        }
+    }
+
     /// Depending on whether we're in Calliper or Callgrind context, this function either:
     /// - respawns self process with modified environment variables to indicate which function
     ///   should be run under Callgrind (Calliper context), or
     /// - runs the function under benchmark (Callgrind context) based on value set in #1.
+    ///
     /// In short, Calliper works by respawning self process under Callgrind and indicating which
     /// function should be ran under Callgrind via environment variable.
+    ///
+    /// Each scenario has a result of it's own, as a failure of one scenario (e.g. a
+    /// [timeout](ScenarioConfig::timeout)) does not prevent others from running.
+    ///
+    /// On SIGINT/SIGTERM, the signal is forwarded to a running Callgrind subprocess. Once it exits,
+    /// temporary Callgrind outputs are removed and the process terminates.
+    ///
+    /// Scenarios can be selected by passing filters to the bench binary, in a similar manner to
+    /// libtest: `cargo bench -- <filter>... [--exact] [--regex] [--skip <filter>]...` (`--regex`
+    /// requires `regex` feature). Other libtest options are ignored. `--list` prints names of
+    /// selected scenarios instead of running them, and `--dry-run` prints shell commands that run
+    /// them under Callgrind (see [`Report::reproduction_command`]).
+    ///
+    /// When the bench binary is run as a test (e.g. with `cargo test --benches`, which does not
+    /// pass `--bench` flag), each function scenario is instead run once natively, without
+    /// Callgrind. This is a quick check that benchmarks do not panic; no results are returned.
+    ///
+    /// Results are passed to registered [reporters](Self::reporter) as they become available and
+    /// are returned as well, in the order of scenarios. See [`Self::run_with`] for handling each
+    /// result as soon as it's available.
+    ///
+    /// If Valgrind is not available, no scenarios are run and an empty list of results is
+    /// returned, unless [`Self::require_valgrind`] is set.
+    ///
+    /// With [`Self::replay`], results are read from existing Callgrind outputs instead.
     pub fn run<'a>(
-        &self,
+        &mut self,
+        settings: impl IntoIterator<Item = &'a Scenario>,
+    ) -> Result<Option<Vec<Result<Report<'a>, CalliperError>>>, CalliperError> {
+        self.run_with(settings, |_| {})
+    }
+
+    /// Same as [`Self::run`], but additionally calls `on_result` with result of each scenario as
+    /// soon as it's Callgrind process has finished (after registered reporters were notified about
+    /// it), rather than once all scenarios are done.
+    ///
+    /// With [parallelism](Self::parallelism), results are passed in order of completion. Results
+    /// of scenarios compared against a [reference scenario](Scenario::reference) are held back
+    /// until the reference has finished.
+    /// ```no_run
+    /// use calliper::{Runner, Scenario};
+    ///
+    /// fn binary_search() {}
+    ///
+    /// let scenarios = [Scenario::new(binary_search)];
+    /// Runner::default()
+    ///     .parallelism(4)
+    ///     .run_with(&scenarios, |result| match result {
+    ///         Ok(report) => println!("{} finished", report.name()),
+    ///         Err(e) => println!("{}", e),
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn run_with<'a>(
+        &mut self,
         settings: impl IntoIterator<Item = &'a Scenario>,
-    ) -> Result<Option<Vec<Report<'a>>>, CalliperError> {
+        on_result: impl FnMut(&Result<Report<'a>, CalliperError>),
+    ) -> Result<Option<Vec<Result<Report<'a>, CalliperError>>>, CalliperError> {
         let run_id = utils::get_run_id();
         let settings: Vec<&Scenario> = settings.into_iter().collect();
         match run_id {
@@ -103,21 +442,336 @@ impl Runner {
                 Ok(None)
             }
             Err(utils::RunIdError::EnvironmentVariableError(std::env::VarError::NotPresent)) => {
-                let outputs = spawn_callgrind(&settings, &self.defaults)?;
-                assert_eq!(outputs.len(), settings.len());
-                let ret = outputs
-                    .into_iter()
-                    .enumerate()
-                    .zip(settings)
-                    .map(|((run_idx, output_path), run)| Report {
-                        run,
-                        run_idx,
-                        results: output_path,
-                    })
-                    .collect();
-                Ok(Some(ret))
+                // Registered reporters are taken for the run, as the runner is borrowed by it, and
+                // kept for subsequent runs.
+                let mut reporters = std::mem::take(&mut self.reporters);
+                let result = self.run_selected(settings, &mut reporters, on_result);
+                self.reporters = reporters;
+                result
             }
             Err(e) => Err(e.into()),
         }
     }
+
+    /// Runs selected scenarios, unless bench binary was only asked to list them.
+    fn run_selected<'a>(
+        &self,
+        settings: Vec<&'a Scenario>,
+        registered: &mut [Box<dyn Reporter + Send + Sync>],
+        mut on_result: impl FnMut(&Result<Report<'a>, CalliperError>),
+    ) -> Result<Option<Vec<Result<Report<'a>, CalliperError>>>, CalliperError> {
+        let args = Arguments::from_env()?;
+        // Run IDs are indices into the list of all scenarios, as that's what Callgrind
+        // subprocess sees.
+        let mut selected: Vec<(usize, &Scenario)> = settings
+            .into_iter()
+            .enumerate()
+            .filter(|(_, run)| args.is_selected(run))
+            .collect();
+        if args.list {
+            args.print_list(selected.iter().map(|(_, run)| *run));
+            return Ok(Some(vec![]));
+        }
+        if args.dry_run {
+            for (run_idx, run) in &selected {
+                let config = self.defaults.clone().overwrite(run.config.clone());
+                println!(
+                    "# {}\n{}",
+                    run.name,
+                    reproduction_command(run, &config, *run_idx)
+                );
+            }
+            return Ok(Some(vec![]));
+        }
+        let replay = args.replay.as_ref().map(PathBuf::from);
+        let replay = replay.as_ref().or(self.replay.as_ref());
+        if replay.is_none() && args.is_test_mode() {
+            let runs: Vec<&Scenario> = selected.iter().map(|(_, run)| *run).collect();
+            Self::run_natively(&runs)?;
+            return Ok(Some(vec![]));
+        }
+        let manifest = match replay {
+            Some(directory) => Some(Manifest::load(directory)?),
+            None => None,
+        };
+        if let Some(manifest) = &manifest {
+            selected.retain(|(_, run)| manifest.contains(&run.name));
+        }
+        let runs: Vec<&Scenario> = selected.iter().map(|(_, run)| *run).collect();
+        // Reporters selected with command line options are used for this run only.
+        let mut selected_reporters: Vec<Box<dyn Reporter>> = vec![];
+        let metric = args.metric.unwrap_or(Metric::InstructionReads);
+        if registered.is_empty() {
+            selected_reporters.push(match args.format {
+                OutputFormat::Pretty => Box::new(TerminalReporter::new().verbose(args.verbose)),
+                OutputFormat::Json => Box::new(JsonReporter::new()),
+                OutputFormat::Csv => Box::new(CsvReporter::new()),
+                OutputFormat::Junit => Box::new(JunitReporter::new()),
+                OutputFormat::Markdown => Box::new(MarkdownReporter::new().metric(metric)),
+                OutputFormat::Bencher => Box::new(BencherReporter::new()),
+                OutputFormat::OpenMetrics => Box::new(OpenMetricsReporter::new()),
+                OutputFormat::Libtest => Box::new(LibtestReporter::new().metric(metric)),
+            });
+        }
+        if args.criterion {
+            let mut reporter = CriterionReporter::new().metric(metric);
+            if let Some(name) = args.save_baseline.as_ref().or(self.save_baseline.as_ref()) {
+                reporter = reporter.baseline(name.clone());
+            }
+            selected_reporters.push(Box::new(reporter));
+        }
+        let mut reporters: Vec<&mut dyn Reporter> = registered
+            .iter_mut()
+            .map(|reporter| reporter.as_mut() as &mut dyn Reporter)
+            .chain(
+                selected_reporters
+                    .iter_mut()
+                    .map(|reporter| reporter.as_mut() as &mut dyn Reporter),
+            )
+            .collect();
+        if let Some(path) = manifest
+            .is_none()
+            .then(|| self.find_missing_valgrind(&runs))
+            .flatten()
+        {
+            if self.is_valgrind_required() {
+                return Err(CalliperError::ValgrindNotFound { path });
+            }
+            eprintln!(
+                "warning: Valgrind executable `{}` could not be found, skipping {} Callgrind scenario(s). Set {}=1 to treat this as an error.",
+                path,
+                runs.len(),
+                utils::CALLIPER_REQUIRE_VALGRIND
+            );
+            // Reporters still produce (empty) output, e.g. a valid JSON document.
+            notify(&mut reporters, |reporter| {
+                reporter.run_started(&[], &self.defaults)
+            })?;
+            notify(&mut reporters, |reporter| reporter.run_finished(&[]))?;
+            return Ok(Some(vec![]));
+        }
+        notify(&mut reporters, |reporter| {
+            reporter.run_started(&runs, &self.defaults)
+        })?;
+        let baseline_name = args.baseline.as_ref().or(self.baseline.as_ref());
+        // Position of a reference scenario of each scenario's group, if it has one.
+        let references: Vec<Option<usize>> = runs
+            .iter()
+            .map(|run| {
+                runs.iter()
+                    .position(|other| other.is_reference && other.group == run.group)
+                    .filter(|_| !run.is_reference)
+            })
+            .collect();
+        let mut finished_references: Vec<(usize, Option<ParsedCallgrindOutput>)> = vec![];
+        // Reports waiting for their reference scenario to finish.
+        let mut waiting: Vec<(usize, Result<Report<'a>, CalliperError>)> = vec![];
+        let mut ret: Vec<Option<Result<Report<'a>, CalliperError>>> =
+            runs.iter().map(|_| None).collect();
+        let bench = utils::bench_name();
+        let mut durations = Durations::of_bench(&bench);
+        let mut progress = match manifest {
+            Some(_) => None,
+            None => Progress::new(
+                runs.iter().map(|run| run.name.clone()).collect(),
+                &durations,
+                self.parallelism,
+            ),
+        };
+        let mut on_event = |event: CallgrindEvent| {
+            let (position, duration, output) = match event {
+                CallgrindEvent::Started(position) => {
+                    if let Some(progress) = &mut progress {
+                        progress.started(position);
+                        progress.draw();
+                    }
+                    return Ok(());
+                }
+                CallgrindEvent::Waiting => {
+                    if let Some(progress) = &mut progress {
+                        progress.draw();
+                    }
+                    return Ok(());
+                }
+                CallgrindEvent::Finished(position, duration, output) => {
+                    (position, duration, output)
+                }
+            };
+            let (run_idx, run) = selected[position];
+            if output.is_ok() && manifest.is_none() {
+                durations.insert(&run.name, duration);
+            }
+            if let Some(progress) = &mut progress {
+                progress.finished(position);
+                // Reporters may print results.
+                progress.clear();
+            }
+            // An unreadable baseline fails the scenario rather than the whole run.
+            let output = output.and_then(|results| {
+                let parsed = parse_callgrind_output(Path::new(&results.path), &run.name)?;
+                let baseline = match baseline_name {
+                    Some(name) => Baseline::load(&run.name, name)?,
+                    None => None,
+                };
+                Ok((results, parsed, baseline))
+            });
+            let result = output.map(|(results, parsed, baseline)| {
+                let mut report = Report {
+                    run,
+                    run_idx,
+                    config: self.defaults.clone().overwrite(run.config.clone()),
+                    results,
+                    parsed,
+                    baseline,
+                    regressions: vec![],
+                    reference: None,
+                };
+                if let Some(comparison) = report.comparison() {
+                    report.regressions = merge_policies(&self.policies, &run.policies)
+                        .filter_map(|policy| policy.check(&comparison))
+                        .collect();
+                }
+                report
+            });
+            let mut finished = vec![(position, result)];
+            if references.contains(&Some(position)) {
+                let results = finished[0].1.as_ref().ok().map(Report::parse);
+                finished_references.push((position, results));
+                let (released, still_waiting) = std::mem::take(&mut waiting)
+                    .into_iter()
+                    .partition(|(waiting, _)| references[*waiting] == Some(position));
+                waiting = still_waiting;
+                finished.extend::<Vec<_>>(released);
+            }
+            for (position, mut result) in finished {
+                if let Some(reference) = references[position] {
+                    match finished_references.iter().find(|(p, _)| *p == reference) {
+                        Some((_, results)) => {
+                            if let Ok(report) = &mut result {
+                                report.reference = results.clone();
+                            }
+                        }
+                        None => {
+                            waiting.push((position, result));
+                            continue;
+                        }
+                    }
+                }
+                match &result {
+                    Ok(report) => notify(&mut reporters, |reporter| {
+                        reporter.scenario_finished(report)
+                    })?,
+                    Err(e) => notify(&mut reporters, |reporter| {
+                        reporter.scenario_failed(runs[position], e)
+                    })?,
+                }
+                on_result(&result);
+                ret[position] = Some(result);
+            }
+            if let Some(progress) = &mut progress {
+                progress.draw();
+            }
+            Ok(())
+        };
+        let interrupted = match &manifest {
+            Some(manifest) => manifest.replay(&selected, &mut on_event).map(|()| None),
+            None => {
+                // Signals are only handled while there are Callgrind processes to stop
+                // and outputs to remove.
+                let interrupts = InterruptHandler::install()?;
+                spawn_callgrind(
+                    &selected,
+                    &self.defaults,
+                    self.parallelism,
+                    &interrupts,
+                    &mut on_event,
+                )
+                .map(|signal| {
+                    // A signal may also arrive once the last Callgrind process has exited,
+                    // e.g. while reporters were notified about it.
+                    signal
+                        .or_else(|| interrupts.received())
+                        .map(|signal| (signal, interrupts))
+                })
+            }
+        };
+        if let Some(progress) = &mut progress {
+            progress.clear();
+        }
+        if let Some((signal, interrupts)) = interrupted? {
+            // Remove outputs of finished scenarios (unless user asked to keep them).
+            drop(waiting);
+            drop(ret);
+            interrupts.terminate(signal);
+        }
+        // Durations are only needed for progress display, so failing to store them is not
+        // worth failing the run over.
+        if progress.is_some() {
+            if let Err(e) = durations.save() {
+                eprintln!("warning: could not store durations of scenarios: {}", e);
+            }
+        }
+        let ret: Vec<Result<Report<'a>, CalliperError>> = ret
+            .into_iter()
+            .map(|result| result.expect("All scenarios must have finished"))
+            .collect();
+        if let Some(name) = args.save_baseline.as_ref().or(self.save_baseline.as_ref()) {
+            for report in ret.iter().flatten() {
+                Baseline::new(
+                    name,
+                    &report.run.name,
+                    report.config.clone(),
+                    report.parse(),
+                )
+                .save()?;
+            }
+        }
+        // Replayed results were recorded when they were collected.
+        if (args.record_history || self.record_history) && manifest.is_none() {
+            let timestamp = utils::unix_timestamp();
+            let commit = utils::git_commit();
+            let records: Vec<HistoryRecord> = ret
+                .iter()
+                .flatten()
+                .map(|report| HistoryRecord {
+                    timestamp,
+                    commit: commit.clone(),
+                    bench: bench.clone(),
+                    scenario: report.run.name.clone(),
+                    results: report.parse(),
+                })
+                .collect();
+            History::of_bench(&bench).append(&records)?;
+        }
+        let html_report = args.html_report.as_ref().map(PathBuf::from);
+        if let Some(path) = html_report.as_ref().or(self.html_report.as_ref()) {
+            HtmlReport::new(&History::of_bench(&bench))
+                .title(format!("{} benchmark history", bench))
+                .write(path)?;
+        }
+        notify(&mut reporters, |reporter| reporter.run_finished(&ret))?;
+        let regressions: Vec<Regression> = ret
+            .iter()
+            .flatten()
+            .flat_map(|report| report.regressions.iter().cloned())
+            .collect();
+        if !regressions.is_empty() {
+            let error = CalliperError::RegressionsDetected { regressions };
+            // `main` returning an error would only show it's `Debug` representation.
+            eprintln!("error: {}", error);
+            return Err(error);
+        }
+        Ok(Some(ret))
+    }
+}
+
+/// Notifies all reporters of a run.
+fn notify(
+    reporters: &mut [&mut dyn Reporter],
+    mut notification: impl FnMut(&mut dyn Reporter) -> Result<(), CalliperError>,
+) -> Result<(), CalliperError> {
+    for reporter in reporters.iter_mut() {
+        notification(*reporter)?;
+    }
+    Ok(())
 }
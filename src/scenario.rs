@@ -1,6 +1,7 @@
 use std::process::Command;
 
 use crate::config::ScenarioConfig;
+use crate::policy::RegressionPolicy;
 use crate::utils::{get_raw_function_name, CALLIPER_RUN_ID};
 
 /// Scenario defines benchmark target and it's auxiliary options.
@@ -10,6 +11,9 @@ pub struct Scenario {
     pub(crate) func: Option<fn()>,
     pub(crate) name: String,
     pub(crate) command: std::process::Command,
+    pub(crate) policies: Vec<RegressionPolicy>,
+    pub(crate) group: Option<String>,
+    pub(crate) is_reference: bool,
 }
 
 impl Scenario {
@@ -26,6 +30,9 @@ impl Scenario {
             func: Some(func),
             name,
             command,
+            policies: vec![],
+            group: None,
+            is_reference: false,
         }
     }
     /// Create a new Scenario for a given command.
@@ -39,6 +46,9 @@ impl Scenario {
             func: None,
             name: Default::default(),
             command,
+            policies: vec![],
+            group: None,
+            is_reference: false,
         }
     }
     /// Override current benchmark name.
@@ -51,4 +61,26 @@ impl Scenario {
         self.config = config;
         self
     }
+    /// Assign scenario to a group. Scenarios within a group are compared against a
+    /// [reference](Self::reference) scenario of that group. Scenarios that are not assigned to
+    /// any group form a group of their own.
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+    /// Mark scenario as a reference of it's group. Results of other scenarios in the group are
+    /// then also reported relative to results of this scenario (see [`ComparisonTable`]).
+    /// If there are multiple reference scenarios in a group, the first one is used.
+    ///
+    /// [`ComparisonTable`]: crate::ComparisonTable
+    pub fn reference(mut self) -> Self {
+        self.is_reference = true;
+        self
+    }
+    /// Add a regression policy for this scenario. It takes precedence over a runner-wide policy
+    /// for the same metric (see [`Runner::policy`](crate::Runner::policy)).
+    pub fn policy(mut self, policy: RegressionPolicy) -> Self {
+        self.policies.push(policy);
+        self
+    }
 }
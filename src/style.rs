@@ -0,0 +1,130 @@
+//! Terminal output styling.
+
+/// Colors used in Calliper output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Color {
+    Red,
+    Green,
+}
+
+impl Color {
+    fn ansi_code(self) -> &'static str {
+        match self {
+            Self::Red => "31",
+            Self::Green => "32",
+        }
+    }
+}
+
+/// Standard output streams.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Stream {
+    Stdout,
+    Stderr,
+}
+
+#[cfg(unix)]
+impl Stream {
+    fn fd(self) -> libc::c_int {
+        match self {
+            Self::Stdout => libc::STDOUT_FILENO,
+            Self::Stderr => libc::STDERR_FILENO,
+        }
+    }
+}
+
+/// Returns true if a given stream is a terminal.
+#[cfg(unix)]
+pub(crate) fn is_terminal(stream: Stream) -> bool {
+    // SAFETY: `isatty` has no memory safety preconditions.
+    unsafe { libc::isatty(stream.fd()) == 1 }
+}
+
+/// Returns true if a given stream is a terminal.
+#[cfg(not(unix))]
+pub(crate) fn is_terminal(_stream: Stream) -> bool {
+    false
+}
+
+/// Returns width of the terminal a given stream is written to, in columns. `COLUMNS` environment
+/// variable takes precedence over the actual size of the terminal. Returns `None` if the stream is
+/// not a terminal, in which case output width should not be limited.
+pub(crate) fn terminal_width(stream: Stream) -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .filter(|&columns| columns > 0)
+    {
+        return Some(columns);
+    }
+    if !is_terminal(stream) {
+        return None;
+    }
+    window_width(stream)
+}
+
+#[cfg(unix)]
+fn window_width(stream: Stream) -> Option<usize> {
+    // SAFETY: `winsize` is a plain C struct, for which all-zeroes is a valid value.
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    // SAFETY: `TIOCGWINSZ` writes into a `winsize` struct, which outlives the call.
+    let ret = unsafe { libc::ioctl(stream.fd(), libc::TIOCGWINSZ, &mut size) };
+    if ret == 0 && size.ws_col > 0 {
+        Some(usize::from(size.ws_col))
+    } else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+fn window_width(_stream: Stream) -> Option<usize> {
+    None
+}
+
+/// Returns true if output may be colored. Output is colored only when written to a terminal.
+/// Colors can also be disabled by setting `NO_COLOR` environment variable (see
+/// <https://no-color.org/>).
+pub(crate) fn is_color_enabled() -> bool {
+    std::env::var_os("NO_COLOR").map_or(true, |value| value.is_empty())
+        && is_terminal(Stream::Stdout)
+}
+
+/// Wraps text in ANSI escape codes for a given color, if colors are enabled.
+pub(crate) fn paint(text: impl core::fmt::Display, color: Color) -> String {
+    if is_color_enabled() {
+        format!("\x1b[{}m{}\x1b[0m", color.ansi_code(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Formats a number with thousands separators, e.g. `1234567` as `1,234,567`.
+pub(crate) fn group_digits(value: impl Into<i128>) -> String {
+    let value = value.into();
+    let digits = value.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+    if value < 0 {
+        grouped.push('-');
+    }
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::group_digits;
+
+    #[test]
+    fn digits_are_grouped_by_thousands() {
+        assert_eq!(group_digits(0u64), "0");
+        assert_eq!(group_digits(999u64), "999");
+        assert_eq!(group_digits(1000u64), "1,000");
+        assert_eq!(group_digits(1234567u64), "1,234,567");
+        assert_eq!(group_digits(-123456i64), "-123,456");
+    }
+}
@@ -1,11 +1,16 @@
 //! Utility functions for benchmarking.
 use std::env;
+use std::io;
 use std::num::ParseIntError;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use backtrace::resolve;
 use thiserror::Error;
 
 pub(super) const CALLIPER_RUN_ID: &str = "CALLIPER_RUN_ID";
+pub(super) const CALLIPER_REQUIRE_VALGRIND: &str = "CALLIPER_REQUIRE_VALGRIND";
 
 /// Errors related to parsing run id in subprocesses.
 #[derive(Clone, Debug, Error, PartialEq)]
@@ -24,6 +29,133 @@ pub(crate) fn get_run_id() -> Result<usize, RunIdError> {
         .and_then(|v| v.parse().map_err(|e: ParseIntError| e.into()))
 }
 
+/// Reads a boolean flag from the environment. Returns `None` if variable is not set or if it's
+/// value is not recognized.
+pub(crate) fn get_env_flag(name: &str) -> Option<bool> {
+    match env::var(name).ok()?.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" | "" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns a directory in which Calliper stores it's data (e.g. baselines), which is
+/// `target/calliper` of the benchmarked crate.
+pub(crate) fn calliper_dir() -> PathBuf {
+    target_dir().join("calliper")
+}
+
+/// Returns target directory of the benchmarked crate.
+///
+/// Target directory is taken from `CARGO_TARGET_DIR` if it's set. Otherwise it is inferred from
+/// location of bench binary (`target/<profile>/deps/<bench>-<hash>`).
+pub(crate) fn target_dir() -> PathBuf {
+    env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .or_else(|| {
+            let exe = env::current_exe().ok()?;
+            let deps = exe.parent()?;
+            if deps.file_name()? != "deps" {
+                return None;
+            }
+            Some(deps.parent()?.parent()?.to_owned())
+        })
+        .unwrap_or_else(|| PathBuf::from("target"))
+}
+
+/// Returns name of current bench binary, with the hash appended by Cargo stripped.
+pub(crate) fn bench_name() -> String {
+    let exe = env::current_exe().ok();
+    let stem = exe
+        .as_ref()
+        .and_then(|exe| exe.file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match stem.rsplit_once('-') {
+        Some((name, hash)) if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+            name.to_owned()
+        }
+        _ => stem,
+    }
+}
+
+/// Turns an arbitrary name into something that can be safely used as a file name. Names that are
+/// safe already are kept as they are. Otherwise unsafe characters and leading dots are replaced,
+/// and a hash of the original name is appended, so that distinct names (e.g. `a b` and `a_b`)
+/// never map to the same file.
+pub(crate) fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_') || (c == '.' && i > 0) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if sanitized == name && !name.is_empty() {
+        sanitized
+    } else {
+        format!("{}-{:08x}", sanitized, fnv1a(name.as_bytes()))
+    }
+}
+
+/// 32-bit FNV-1a hash, which unlike `std` hashers is guaranteed to be stable across Rust versions.
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Renders text with a function writing to a `core::fmt::Write`.
+pub(crate) fn render_string(
+    render: impl FnOnce(&mut String) -> core::fmt::Result,
+) -> io::Result<String> {
+    let mut out = String::new();
+    render(&mut out).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    Ok(out)
+}
+
+/// Escapes characters that are special in HTML and XML.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns number of seconds since Unix epoch.
+pub(crate) fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Returns hash of currently checked out Git commit, if there's one.
+pub(crate) fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let commit = String::from_utf8(output.stdout).ok()?.trim().to_owned();
+    Some(commit).filter(|commit| !commit.is_empty())
+}
+
 /// Returns true if the process is not running under Callgrind.
 pub fn is_setup_run() -> bool {
     get_run_id().is_err()
@@ -49,6 +181,7 @@ pub fn black_box<T>(dummy: T) -> T {
     }
 }
 #[rustversion::since(1.66)]
+#[allow(clippy::incompatible_msrv)]
 pub use std::hint::black_box;
 
 /// Given a function pointer, resolve it's mangled name.
@@ -72,4 +205,38 @@ mod tests {
             assert_eq!(get_raw_function_name(foo), "foo");
         }
     }
+    mod escape {
+        use crate::utils::escape;
+        #[test]
+        fn escapes_special_characters() {
+            assert_eq!(escape("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+        }
+    }
+    mod sanitize_file_name {
+        use crate::utils::sanitize_file_name;
+        #[test]
+        fn replaces_path_separators() {
+            assert!(sanitize_file_name("foo/bar baz.1").starts_with("foo_bar_baz.1-"));
+        }
+        #[test]
+        fn does_not_escape_directory() {
+            assert!(sanitize_file_name("..").starts_with("_.-"));
+            assert!(sanitize_file_name(".").starts_with("_-"));
+            assert!(sanitize_file_name("").starts_with('-'));
+        }
+        #[test]
+        fn distinct_names_do_not_collide() {
+            assert_eq!(sanitize_file_name("a b"), sanitize_file_name("a b"));
+            assert_ne!(sanitize_file_name("a b"), sanitize_file_name("a_b"));
+            assert_eq!(sanitize_file_name("a b"), "a_b-10a3f9f2");
+        }
+        #[test]
+        fn keeps_safe_names() {
+            assert_eq!(sanitize_file_name("a"), "a");
+            assert_eq!(
+                sanitize_file_name("binary_search-v2.1"),
+                "binary_search-v2.1"
+            );
+        }
+    }
 }